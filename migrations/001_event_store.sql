@@ -0,0 +1,39 @@
+-- Written so it also upgrades databases set up before migrations were
+-- recorded, which may hold any earlier version of these tables.
+
+create table if not exists events (
+    id bigserial primary key,
+    aggregate_type text not null,
+    aggregate_id bigint,
+    ts timestamp default current_timestamp,
+    payload text,
+    version bigint
+);
+
+alter table events add column if not exists aggregate_type text not null default 'contract';
+drop index if exists events_agg;
+drop index if exists events_agg_version;
+create index if not exists events_type_agg_version on events (aggregate_type, aggregate_id, version);
+
+create table if not exists aggregates (
+    aggregate_type text not null,
+    id bigint not null,
+    v bigint
+);
+
+create table if not exists snapshots (
+    aggregate_type text not null,
+    id bigint not null,
+    v bigint not null,
+    ts timestamp default current_timestamp,
+    snapshot text not null
+);
+
+-- aggregates and snapshots used to be keyed on id alone, before more
+-- than one kind of aggregate lived in the store
+alter table aggregates add column if not exists aggregate_type text not null default 'contract';
+alter table aggregates drop constraint if exists aggregates_pkey;
+create unique index if not exists aggregates_type_id on aggregates (aggregate_type, id);
+alter table snapshots add column if not exists aggregate_type text not null default 'contract';
+alter table snapshots drop constraint if exists snapshots_pkey;
+create unique index if not exists snapshots_type_id on snapshots (aggregate_type, id);
@@ -0,0 +1,6 @@
+create table if not exists transfers (
+    id text primary key,
+    ts timestamp default current_timestamp,
+    finished boolean not null default false,
+    payload text not null
+);
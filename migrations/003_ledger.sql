@@ -0,0 +1,17 @@
+create table if not exists projections (
+    name text primary key,
+    position bigint not null
+);
+
+create table if not exists ledger (
+    id bigserial primary key,
+    event_id bigint not null,
+    aggregate_id bigint not null,
+    credit_type text not null,
+    account text not null,
+    reference text,
+    debit bigint not null default 0,
+    credit bigint not null default 0
+);
+
+create index if not exists ledger_agg_ref on ledger (aggregate_id, reference);
@@ -0,0 +1,12 @@
+-- Events saved in one batch used to share the final version of the batch.
+-- Aggregates start at version 0 and every event bumps it by one, so the
+-- version an event should have is its position in the aggregate's history.
+update events e set version = numbered.version
+from (
+    select id, row_number() over (partition by aggregate_type, aggregate_id order by id) as version
+    from events
+) numbered
+where e.id = numbered.id and e.version is distinct from numbered.version;
+
+drop index if exists events_type_agg_version;
+create unique index events_type_agg_version on events (aggregate_type, aggregate_id, version);
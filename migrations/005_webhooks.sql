@@ -0,0 +1,29 @@
+-- Endpoints the events of an account are posted to
+create table if not exists webhooks (
+    id bigserial primary key,
+    aggregate_id bigint not null,
+    url text not null,
+    secret text not null,
+    -- names of the events posted, null for all of them
+    events text[],
+    created_at timestamp not null default current_timestamp
+);
+
+create index if not exists webhooks_aggregate on webhooks (aggregate_id);
+
+-- Every event owed to a webhook and how delivering it went. Deliveries are
+-- pending until the endpoint takes them, and dead once retries run out.
+create table if not exists webhook_deliveries (
+    id bigserial primary key,
+    webhook_id bigint not null references webhooks (id) on delete cascade,
+    event_id bigint not null,
+    status text not null default 'pending',
+    attempts int not null default 0,
+    next_attempt_at timestamp not null default current_timestamp,
+    last_response int,
+    last_error text,
+    delivered_at timestamp,
+    unique (webhook_id, event_id)
+);
+
+create index if not exists webhook_deliveries_due on webhook_deliveries (next_attempt_at) where status = 'pending';
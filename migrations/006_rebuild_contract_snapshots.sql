@@ -0,0 +1,3 @@
+-- Contract snapshots taken before refunds left out the spends a refund needs.
+-- Contracts are rebuilt from their events on next load and snapshotted afresh.
+delete from snapshots where aggregate_type = 'contract';
@@ -0,0 +1,10 @@
+-- Projections read events in the order of the transactions that wrote them,
+-- taking only those of transactions older than any still running, so an event
+-- committed after others with higher ids is never passed over. Existing events
+-- and checkpoints all get this migration's transaction id, which keeps them in
+-- id order.
+alter table events add column if not exists txid bigint not null default txid_current();
+alter table projections add column if not exists txid bigint not null default txid_current();
+alter table projections alter column txid drop default;
+
+create index if not exists events_txid on events (aggregate_type, txid, id);
@@ -0,0 +1,74 @@
+use chrono::NaiveDateTime;
+use serde_json;
+
+use ledger::{events_after_checkpoint, save_checkpoint};
+use {Amount, CreditError, CreditEvent, CreditType, MyPool};
+
+// name of this projection's checkpoint in the projections table
+const PROJECTION: &str = "alerts";
+
+// Available credits of an account having dropped below the threshold set
+// for them
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BalanceAlert {
+    pub account: i64,
+    pub credit_type: CreditType,
+    pub threshold: Amount,
+    pub available: Amount,
+    pub ts: NaiveDateTime
+}
+
+// Where alerts are sent. A failed alert is tried again on the next run along
+// with those after it, so a sink may see an alert more than once.
+pub trait NotificationSink {
+    fn notify(&self, alert: &BalanceAlert) -> Result<(), CreditError>;
+}
+
+// Writes alerts to the log, for when nothing else is listening
+pub struct LogSink;
+
+impl NotificationSink for LogSink {
+    fn notify(&self, alert: &BalanceAlert) -> Result<(), CreditError> {
+        warn!(account = alert.account, credit_type = %alert.credit_type, threshold = alert.threshold,
+            available = alert.available, "balance below threshold");
+        Ok(())
+    }
+}
+
+fn alert(account: i64, ts: NaiveDateTime, evt: CreditEvent) -> Option<BalanceAlert> {
+    match evt {
+        CreditEvent::BalanceThresholdCrossed { credit_type, threshold, available } =>
+            Some(BalanceAlert { account, credit_type, threshold, available, ts }),
+        _ => None
+    }
+}
+
+// Sends the alerts raised since the last run to the sink, stopping at the
+// first it fails to take. Returns how many were sent.
+pub fn deliver<S: NotificationSink>(pool: &MyPool, sink: &S) -> Result<usize, CreditError> {
+    let conn = pool.get()?;
+    let trx = conn.transaction()?;
+
+    let (checkpoint, events) = events_after_checkpoint(&trx, PROJECTION)?;
+
+    let (mut last, mut sent, mut result) = (checkpoint, 0, Ok(()));
+    for event in events.iter() {
+        let evt: CreditEvent = serde_json::from_str(&event.payload)?;
+        if let Some(alert) = alert(event.aggregate_id, event.ts, evt) {
+            if let Err(e) = sink.notify(&alert) {
+                result = Err(e);
+                break
+            }
+            sent += 1;
+        }
+        last = event.checkpoint();
+    }
+
+    save_checkpoint(&trx, PROJECTION, last)?;
+    trx.commit()?;
+
+    if sent > 0 {
+        debug!(alerts = sent, "delivered balance alerts");
+    }
+    result.map(|()| sent)
+}
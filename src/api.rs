@@ -0,0 +1,142 @@
+use eventstore::{load, run_and_store};
+use person;
+use {Contract, CreditCommand, CreditError, CreditType, DEFAULT_CREDIT_TYPE, MyPool};
+
+// What went wrong, in terms both the gRPC and the HTTP server can translate
+// into their own status codes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorKind {
+    // the request makes no sense whatever state the account is in
+    InvalidArgument,
+    // a reservation, allocation or spending referred to doesn't exist
+    NotFound,
+    // something with the same id exists already
+    AlreadyExists,
+    // the request is fine but the account's state doesn't allow it
+    FailedPrecondition,
+    // the caller didn't prove who they are
+    Unauthenticated,
+    // the caller isn't allowed to do this
+    PermissionDenied,
+    // the caller has used up a quota, retry later
+    ResourceExhausted,
+    // somebody else changed the aggregate first, safe to retry
+    Conflict,
+    // storage or data problems on our side
+    Internal
+}
+
+impl CreditError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            CreditError::InvalidAmount(_) |
+            CreditError::TransferToSelf |
+            CreditError::InvalidReservationId(_) |
+            CreditError::InvalidImport(_) |
+            CreditError::InvalidRequest(_) => ErrorKind::InvalidArgument,
+            CreditError::ReservationNotFound |
+            CreditError::AllocationNotFound |
+            CreditError::SpendingNotFound => ErrorKind::NotFound,
+            CreditError::ReservationAlreadyExists => ErrorKind::AlreadyExists,
+            CreditError::NotEnoughMoney { .. } |
+            CreditError::RefundTooLarge { .. } |
+            CreditError::AccountFrozen |
+            CreditError::AccountClosed |
+            CreditError::ContractAlreadyOwned |
+            CreditError::ContractNotAttached |
+            CreditError::ReservationLimitExceeded { .. } => ErrorKind::FailedPrecondition,
+            CreditError::Unauthenticated(_) => ErrorKind::Unauthenticated,
+            CreditError::PermissionDenied => ErrorKind::PermissionDenied,
+            CreditError::QuotaExceeded { .. } => ErrorKind::ResourceExhausted,
+            CreditError::ConcurrencyError => ErrorKind::Conflict,
+            CreditError::StorageError(_) | CreditError::PoolError(_) | CreditError::DataError(_) => ErrorKind::Internal
+        }
+    }
+
+    // name of the variant, used to label metrics
+    pub fn name(&self) -> &'static str {
+        match self {
+            CreditError::NotEnoughMoney { .. } => "NotEnoughMoney",
+            CreditError::InvalidAmount(_) => "InvalidAmount",
+            CreditError::ReservationAlreadyExists => "ReservationAlreadyExists",
+            CreditError::ReservationNotFound => "ReservationNotFound",
+            CreditError::AllocationNotFound => "AllocationNotFound",
+            CreditError::SpendingNotFound => "SpendingNotFound",
+            CreditError::RefundTooLarge { .. } => "RefundTooLarge",
+            CreditError::TransferToSelf => "TransferToSelf",
+            CreditError::AccountFrozen => "AccountFrozen",
+            CreditError::AccountClosed => "AccountClosed",
+            CreditError::ContractAlreadyOwned => "ContractAlreadyOwned",
+            CreditError::ContractNotAttached => "ContractNotAttached",
+            CreditError::ReservationLimitExceeded { .. } => "ReservationLimitExceeded",
+            CreditError::QuotaExceeded { .. } => "QuotaExceeded",
+            CreditError::InvalidReservationId(_) => "InvalidReservationId",
+            CreditError::InvalidImport(_) => "InvalidImport",
+            CreditError::InvalidRequest(_) => "InvalidRequest",
+            CreditError::Unauthenticated(_) => "Unauthenticated",
+            CreditError::PermissionDenied => "PermissionDenied",
+            CreditError::ConcurrencyError => "ConcurrencyError",
+            CreditError::StorageError(_) => "StorageError",
+            CreditError::PoolError(_) => "PoolError",
+            CreditError::DataError(_) => "DataError"
+        }
+    }
+
+    // seconds a client should wait before trying again, if waiting helps
+    pub fn retry_after(&self) -> Option<i64> {
+        match self {
+            CreditError::QuotaExceeded { retry_after, .. } => *retry_after,
+            _ => None
+        }
+    }
+
+    // Description safe to hand to clients. Internal errors are logged rather
+    // than passed on, they may hold details of the database.
+    pub fn message(&self) -> String {
+        match self.kind() {
+            ErrorKind::Internal => {
+                error!(error = ?self, "internal error");
+                "internal error".to_string()
+            },
+            _ => format!("{:?}", self)
+        }
+    }
+}
+
+// requests leaving out the credit type mean the default one
+pub fn credit_type(t: &str) -> CreditType {
+    if t.is_empty() { DEFAULT_CREDIT_TYPE } else { t }.to_string()
+}
+
+// Runs a command against an account on behalf of a client, returning the
+// account as it is afterwards. Reservations have to be approved by the
+// customer owning the account, everything else goes straight to it.
+pub fn execute(account: i64, cmd: CreditCommand, pool: &MyPool) -> Result<Contract, CreditError> {
+    match cmd {
+        CreditCommand::ReserveCredits(id, credit_type, amount) =>
+            person::reserve_credits(account, id, credit_type, amount, pool),
+        cmd => {
+            let mut c: Contract = load(account, pool)?;
+            run_and_store(&mut c, cmd, pool)?;
+            Ok(c)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn business_errors_are_not_internal() {
+        assert_eq!(CreditError::NotEnoughMoney { has: 1, needs: 2 }.kind(), ErrorKind::FailedPrecondition);
+        assert_eq!(CreditError::ReservationNotFound.kind(), ErrorKind::NotFound);
+        assert_eq!(CreditError::ConcurrencyError.kind(), ErrorKind::Conflict);
+    }
+
+    #[test]
+    fn missing_credit_type_means_default() {
+        assert_eq!(credit_type(""), DEFAULT_CREDIT_TYPE);
+        assert_eq!(credit_type("gold"), "gold");
+    }
+}
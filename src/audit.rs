@@ -0,0 +1,232 @@
+use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{self, Value};
+use uuid::Uuid;
+
+use eventstore::{aggregate_versions, get_events, load_snapshot_value};
+use person::Person;
+use {Aggregate, Amount, Contract, CreditError, CreditEvent, MyPool};
+use CreditEvent::*;
+
+// Consistency rules an aggregate is held to while its events are replayed
+pub trait Audited: Aggregate {
+    // problem preventing the event from being applied to the current state
+    fn check_event(&self, _evt: &Self::Item) -> Option<String> {
+        None
+    }
+
+    // problems with the state reached after applying the whole history
+    fn check_state(&self, _history: &[Self::Item]) -> Vec<String> {
+        vec![]
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Discrepancy {
+    pub aggregate_type: &'static str,
+    pub aggregate_id: i64,
+    pub problem: String
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct AuditReport {
+    pub aggregates: usize,
+    pub events: usize,
+    pub discrepancies: Vec<Discrepancy>
+}
+
+impl Audited for Contract {
+    fn check_event(&self, evt: &CreditEvent) -> Option<String> {
+        let reservation = |id: &Uuid| self.reservations.get(id).map(|r| r.amount);
+
+        match evt {
+            CreditsReserved { id, .. } if self.reservations.contains_key(id) =>
+                Some(format!("reservation {} already exists", id)),
+            CreditsAllocated { id, .. } |
+            ReservationSpent { id, .. } |
+            ReservationExpired { id, .. } if reservation(id).is_none() =>
+                Some(format!("reservation {} not found", id)),
+            ReservationCancelled(id, amount) => match reservation(id) {
+                None => Some(format!("reservation {} not found", id)),
+                Some(a) if a != *amount => Some(format!("reservation {} holds {}, cancelled {}", id, a, amount)),
+                Some(_) => None
+            },
+            AllocationFreed { id, .. } if !self.allocations.contains_key(id) =>
+                Some(format!("allocation {} not found", id)),
+            SpendingRefunded { id, amount } => match self.spendings.get(id) {
+                None => Some(format!("spending {} not found", id)),
+                Some(s) if s.amount < *amount => Some(format!("spending {} holds {}, refunded {}", id, s.amount, amount)),
+                Some(_) => None
+            },
+            _ => None
+        }
+    }
+
+    fn check_state(&self, history: &[CreditEvent]) -> Vec<String> {
+        let mut issued: HashMap<&str, Amount> = HashMap::new();
+        for evt in history {
+            match evt {
+                CreditsAdded { credit_type, amount } => *issued.entry(credit_type).or_insert(0) += amount,
+                CreditsRemoved { credit_type, amount, .. } => *issued.entry(credit_type).or_insert(0) -= amount,
+                _ => {}
+            }
+        }
+
+        let mut types: HashSet<&str> = issued.keys().cloned().collect();
+        types.extend(self.balances.keys().map(|t| t.as_str()));
+
+        let mut problems = vec![];
+        for t in types {
+            let held = |m: &HashMap<_, ::CreditReservation>| -> Amount {
+                m.values().filter(|r| r.credit_type == t).map(|r| r.amount).sum()
+            };
+            let balance = self.balance(t);
+            let reserved = held(&self.reservations);
+            let allocated = held(&self.allocations);
+            let spendings = held(&self.spendings);
+            let total = issued.get(t).cloned().unwrap_or(0);
+
+            if balance.amount + reserved + allocated + balance.spent != total {
+                problems.push(format!(
+                    "{}: amount {} + reserved {} + allocated {} + spent {} does not add up to {} added",
+                    t, balance.amount, reserved, allocated, balance.spent, total));
+            }
+            if balance.spent != spendings {
+                problems.push(format!("{}: spent {} but spendings hold {}", t, balance.spent, spendings));
+            }
+        }
+        problems
+    }
+}
+
+impl Audited for Person {}
+
+// Replays a single aggregate from scratch, returning every problem found.
+// The snapshot, if any, is compared to the replayed state at its version.
+pub fn replay<A>(id: i64, stored_version: i64, history: &[A::Item], snapshot: Option<&Value>) -> Vec<String>
+    where A: Audited + Serialize + DeserializeOwned
+{
+    let mut a = A::new(id);
+    let mut problems = vec![];
+    let snapshot_version = snapshot.and_then(|s| s.get("version")).and_then(|v| v.as_i64());
+
+    for evt in history {
+        if let Some(p) = a.check_event(evt) {
+            problems.push(format!("event {} cannot be applied: {}", a.version()+1, p));
+            return problems;
+        }
+        a.apply(evt);
+
+        if Some(a.version()) == snapshot_version {
+            problems.extend(compare_snapshot(&a, snapshot));
+        }
+    }
+
+    if let Some(v) = snapshot_version {
+        if v > a.version() {
+            problems.push(format!("snapshot at version {} is ahead of the {} events stored", v, a.version()));
+        }
+    }
+
+    if a.version() != stored_version {
+        problems.push(format!("replayed to version {} but version {} is recorded", a.version(), stored_version));
+    }
+
+    problems.extend(a.check_state(history));
+    problems
+}
+
+// The snapshot is read the way loading would read it, so one written before
+// a change to the aggregate's shape is compared by what it holds rather than
+// by how it was laid out
+fn compare_snapshot<A: Serialize + DeserializeOwned>(a: &A, snapshot: Option<&Value>) -> Option<String> {
+    let snap = match snapshot.map(|s| serde_json::from_value::<A>(s.clone())) {
+        Some(Ok(snap)) => snap,
+        Some(Err(e)) => return Some(format!("snapshot cannot be read: {}", e)),
+        None => return None
+    };
+    match (serde_json::to_value(a), serde_json::to_value(&snap)) {
+        (Ok(ref replayed), Ok(ref snap)) if replayed != snap =>
+            Some(format!("snapshot differs from replayed state: {} != {}", snap, replayed)),
+        _ => None
+    }
+}
+
+fn audit_type<A>(pool: &MyPool, report: &mut AuditReport) -> Result<(), CreditError>
+    where A: Audited + Serialize + DeserializeOwned, A::Item: DeserializeOwned
+{
+    for (id, stored_version) in aggregate_versions::<A>(pool)? {
+        let history = get_events::<A>(id, 0, pool)?;
+        let snapshot = load_snapshot_value::<A>(id, pool)?;
+        report.aggregates += 1;
+        report.events += history.len();
+
+        for problem in replay::<A>(id, stored_version, &history, snapshot.as_ref()) {
+            report.discrepancies.push(Discrepancy { aggregate_type: A::TYPE, aggregate_id: id, problem });
+        }
+    }
+    Ok(())
+}
+
+// Replays every aggregate in the store and checks it against its invariants,
+// snapshot and recorded version
+pub fn audit(pool: &MyPool) -> Result<AuditReport, CreditError> {
+    let mut report = AuditReport::default();
+    audit_type::<Contract>(pool, &mut report)?;
+    audit_type::<Person>(pool, &mut report)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use default_credit_type;
+
+    fn history(id: Uuid) -> Vec<CreditEvent> {
+        vec![
+            CreditsAdded { credit_type: default_credit_type(), amount: 10 },
+            CreditsReserved { id, credit_type: default_credit_type(), amount: 4, timestamp: Utc::now() },
+            ReservationSpent { id, amount: 4 },
+        ]
+    }
+
+    #[test]
+    fn consistent_history_has_no_problems() {
+        let h = history(Uuid::new_v4());
+        assert!(replay::<Contract>(1, 3, &h, None).is_empty());
+    }
+
+    #[test]
+    fn it_flags_event_for_missing_reservation() {
+        let mut h = history(Uuid::new_v4());
+        h.push(ReservationCancelled(Uuid::new_v4(), 4));
+        assert_eq!(replay::<Contract>(1, 4, &h, None).len(), 1);
+    }
+
+    #[test]
+    fn it_flags_wrong_version() {
+        let h = history(Uuid::new_v4());
+        assert_eq!(replay::<Contract>(1, 5, &h, None).len(), 1);
+    }
+
+    #[test]
+    fn it_flags_stale_snapshot() {
+        let h = history(Uuid::new_v4());
+        let mut snap = Contract::new(1);
+        snap.apply(&h[0]);
+        snap.version = 2;
+        let snap = serde_json::to_value(&snap).unwrap();
+        assert_eq!(replay::<Contract>(1, 3, &h, Some(&snap)).len(), 1);
+    }
+
+    #[test]
+    fn it_reads_snapshots_from_before_credit_types() {
+        let h = history(Uuid::new_v4());
+        let snap: Value = serde_json::from_str(
+            r#"{"id":1,"version":1,"amount":10,"spent":0,"reservations":{},"allocations":{},"credit_limit":0,"state":"Open","owner":null}"#)
+            .unwrap();
+        assert!(replay::<Contract>(1, 3, &h, Some(&snap)).is_empty());
+    }
+}
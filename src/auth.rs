@@ -0,0 +1,176 @@
+use base64::{self, URL_SAFE_NO_PAD};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde_json::{self, Value};
+use sha2::Sha256;
+
+use config::Config;
+use eventstore::load;
+use {Contract, CreditError, MyPool, PersonId};
+
+// the only header tokens are signed with
+const HEADER: &str = r#"{"alg":"HS256","typ":"JWT"}"#;
+
+// Who is making a request, as carried in the claims of their token. Tokens
+// are JSON Web Tokens signed with HMAC-SHA256 using the configured key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Principal {
+    // name of the service or person the token was issued to
+    pub sub: String,
+    // may do anything
+    #[serde(default)]
+    pub admin: bool,
+    // customer whose accounts a service may use
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub customer: Option<PersonId>,
+    // seconds since the epoch after which the token is refused
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>
+}
+
+impl Principal {
+    pub fn admin(sub: &str) -> Self {
+        Principal { sub: sub.to_string(), admin: true, customer: None, exp: None }
+    }
+
+    pub fn service(sub: &str, customer: PersonId) -> Self {
+        Principal { sub: sub.to_string(), admin: false, customer: Some(customer), exp: None }
+    }
+}
+
+// What a request needs to be allowed to touch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Access {
+    // adding credits, attaching contracts and reading the ledger
+    Admin,
+    // using an account, allowed to services acting for its owner
+    Account(i64),
+    // reading a customer, allowed to services acting for them
+    Customer(PersonId)
+}
+
+// HMAC-SHA256 of data, also signing webhook deliveries
+pub fn mac(key: &[u8], data: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_varkey(key).expect("hmac takes keys of any length");
+    mac.input(data.as_bytes());
+    mac
+}
+
+#[derive(Clone)]
+pub struct Auth {
+    key: Option<Vec<u8>>,
+    disabled: bool
+}
+
+impl Auth {
+    // With auth disabled every caller is let through as an admin, as before
+    // there was authentication. Otherwise callers are turned away when there
+    // is no key to check their tokens with.
+    pub fn new(config: &Config) -> Self {
+        Auth { key: config.auth_key.as_ref().map(|k| k.as_bytes().to_vec()), disabled: config.auth_disabled }
+    }
+
+    pub fn issue(&self, principal: &Principal) -> Result<String, CreditError> {
+        let key = self.key.as_ref().ok_or_else(|| CreditError::InvalidRequest("no auth_key set".to_string()))?;
+        let claims = base64::encode_config(&serde_json::to_vec(principal)?, URL_SAFE_NO_PAD);
+        let signed = format!("{}.{}", base64::encode_config(HEADER, URL_SAFE_NO_PAD), claims);
+        let signature = base64::encode_config(&mac(key, &signed).result().code(), URL_SAFE_NO_PAD);
+        Ok(format!("{}.{}", signed, signature))
+    }
+
+    fn verify(&self, key: &[u8], token: &str) -> Result<Principal, CreditError> {
+        let invalid = |reason: &str| CreditError::Unauthenticated(reason.to_string());
+        let decode = |part: &str| base64::decode_config(part, URL_SAFE_NO_PAD).map_err(|_| invalid("malformed token"));
+
+        let parts: Vec<&str> = token.split('.').collect();
+        if parts.len() != 3 {
+            return Err(invalid("malformed token"))
+        }
+        let header: Value = serde_json::from_slice(&decode(parts[0])?).map_err(|_| invalid("malformed token"))?;
+        if header["alg"] != "HS256" {
+            return Err(invalid("unsupported algorithm"))
+        }
+        let signed = &token[..parts[0].len() + 1 + parts[1].len()];
+        mac(key, signed).verify(&decode(parts[2])?).map_err(|_| invalid("bad signature"))?;
+
+        let principal: Principal = serde_json::from_slice(&decode(parts[1])?).map_err(|_| invalid("malformed claims"))?;
+        match principal.exp {
+            Some(exp) if exp <= Utc::now().timestamp() => Err(invalid("token expired")),
+            _ => Ok(principal)
+        }
+    }
+
+    // Who is calling, from the value of the authorization header or metadata
+    pub fn authenticate(&self, authorization: Option<&str>) -> Result<Principal, CreditError> {
+        if self.disabled {
+            return Ok(Principal::admin("anonymous"))
+        }
+        let key = self.key.as_ref().ok_or_else(|| CreditError::Unauthenticated("no auth_key set".to_string()))?;
+        let value = authorization.ok_or_else(|| CreditError::Unauthenticated("missing token".to_string()))?;
+        let mut parts = value.splitn(2, ' ');
+        match (parts.next(), parts.next()) {
+            (Some(scheme), Some(token)) if scheme.eq_ignore_ascii_case("bearer") => self.verify(key, token.trim()),
+            _ => Err(CreditError::Unauthenticated("expected a bearer token".to_string()))
+        }
+    }
+}
+
+// Admins may do anything. Services may only use accounts owned by the
+// customer they act for.
+pub fn authorize(principal: &Principal, access: Access, pool: &MyPool) -> Result<(), CreditError> {
+    if principal.admin {
+        return Ok(())
+    }
+    let allowed = match (access, principal.customer) {
+        (Access::Account(account), Some(customer)) => {
+            let c: Contract = load(account, pool)?;
+            c.owner == Some(customer)
+        },
+        (Access::Customer(id), Some(customer)) => id == customer,
+        _ => false
+    };
+    if allowed {
+        Ok(())
+    } else {
+        debug!(principal = %principal.sub, access = ?access, "permission denied");
+        Err(CreditError::PermissionDenied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth(key: &str) -> Auth {
+        Auth { key: Some(key.as_bytes().to_vec()), disabled: false }
+    }
+
+    #[test]
+    fn it_accepts_tokens_it_issued() {
+        let a = auth("secret");
+        let token = a.issue(&Principal::service("billing", 7)).unwrap();
+        let p = a.authenticate(Some(&format!("Bearer {}", token))).unwrap();
+        assert_eq!(p, Principal::service("billing", 7));
+    }
+
+    #[test]
+    fn it_rejects_bad_tokens() {
+        let token = auth("other").issue(&Principal::admin("ops")).unwrap();
+        auth("secret").authenticate(Some(&format!("Bearer {}", token))).expect_err("signed with another key");
+        auth("secret").authenticate(None).expect_err("no token");
+        auth("secret").authenticate(Some("Basic b3BzOm9wcw==")).expect_err("not a bearer token");
+
+        let mut expired = Principal::admin("ops");
+        expired.exp = Some(Utc::now().timestamp() - 1);
+        let token = auth("secret").issue(&expired).unwrap();
+        auth("secret").authenticate(Some(&format!("Bearer {}", token))).expect_err("expired");
+    }
+
+    #[test]
+    fn it_fails_closed_without_a_key() {
+        let a = Auth { key: None, disabled: false };
+        a.authenticate(None).expect_err("no key to check against");
+        let a = Auth { key: None, disabled: true };
+        assert_eq!(a.authenticate(None).unwrap(), Principal::admin("anonymous"));
+    }
+}
@@ -0,0 +1,236 @@
+extern crate credits;
+extern crate chrono;
+extern crate serde;
+extern crate serde_json;
+extern crate uuid;
+
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::process;
+use std::str::FromStr;
+use chrono::Utc;
+use uuid::Uuid;
+
+use credits::{audit, eventstore, export, webhooks, logging, migrations, Aggregate, Contract, CreditCommand, CreditError, MyPool, Quota, default_credit_type};
+use credits::auth::{Auth, Principal};
+use credits::person::Person;
+use credits::config::Config;
+
+const USAGE: &str = "usage: credits-admin <command>
+
+commands:
+    init                        apply schema migrations not yet applied
+    inspect <account>           print an account and its reservations
+    events <account>            dump the events of an account
+    replay                      replay every aggregate and report discrepancies
+    rebuild-snapshots           rebuild every snapshot from events
+    evict <account> <age>       expire reservations older than age seconds
+    add <account> <amount> [type]
+                                add credits to an account
+    remove <account> <amount> <reason> [type]
+                                remove credits from an account, stating why
+    refund <account> <spending> <amount>
+                                return spent credits to an account
+    credit-limit <account> <type> <limit>
+                                let reservations take a credit type this far below zero
+    freeze <account>            turn away new reservations on an account
+    unfreeze <account>          take reservations on a frozen account again
+    close <account>             close an account for good, refusing any further command
+    quota <account> [<amount> <window>]
+                                cap credits reserved per window of seconds, or lift the cap
+    alert <account> <type> [threshold]
+                                alert when available credits drop below threshold, or stop
+    webhook add <account> <url> <secret> [event...]
+                                post events of an account to url, signed with secret,
+                                only those named if any are
+    webhook remove <id>         stop posting to a webhook
+    webhook dead                list deliveries that ran out of attempts
+    webhook retry <delivery>    attempt a dead delivery again
+    export [from] [to]          write events of accounts in the range to stdout
+    import                      load exported events from stdin into an empty store
+    token admin <name>          issue a bearer token allowed to do anything
+    token service <name> <customer>
+                                issue a bearer token for the accounts of a customer";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn arg<T: FromStr>(args: &[String], i: usize) -> T {
+    match args.get(i).map(|a| a.parse()) {
+        Some(Ok(v)) => v,
+        _ => usage()
+    }
+}
+
+fn init(pool: &MyPool) -> Result<(), CreditError> {
+    for version in migrations::migrate(pool)? {
+        println!("applied migration {}", version);
+    }
+    Ok(())
+}
+
+fn inspect(account: i64, pool: &MyPool) -> Result<(), CreditError> {
+    let c: Contract = eventstore::load(account, pool)?;
+    println!("{}", serde_json::to_string_pretty(&c)?);
+    for (id, r) in c.reservations() {
+        println!("{}\t{}\t{}\t{}", id, r.credit_type, r.amount, r.created_time);
+    }
+    Ok(())
+}
+
+fn events(account: i64, pool: &MyPool) -> Result<(), CreditError> {
+    for evt in eventstore::get_events::<Contract>(account, 0, pool)? {
+        println!("{}", serde_json::to_string(&evt)?);
+    }
+    Ok(())
+}
+
+// exits non-zero when anything doesn't add up, so it can run from cron
+fn replay(pool: &MyPool) -> Result<(), CreditError> {
+    let report = audit::audit(pool)?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    if !report.discrepancies.is_empty() {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn rebuild_snapshots<A>(pool: &MyPool) -> Result<(), CreditError>
+    where A: Aggregate + serde::Serialize, A::Item: serde::de::DeserializeOwned
+{
+    for (id, _) in eventstore::aggregate_versions::<A>(pool)? {
+        let version = eventstore::rebuild_snapshot::<A>(id, pool)?;
+        println!("{} {} at {}", A::TYPE, id, version);
+    }
+    Ok(())
+}
+
+fn run(cmd: CreditCommand, account: i64, pool: &MyPool) -> Result<(), CreditError> {
+    let mut c: Contract = eventstore::load(account, pool)?;
+    eventstore::run_and_store(&mut c, cmd, pool)?;
+    println!("{} at {}", account, c.version());
+    Ok(())
+}
+
+fn export_events(args: &[String], pool: &MyPool) -> Result<(), CreditError> {
+    let from = args.get(1).map(|_| arg(args, 1));
+    let to = args.get(2).map(|_| arg(args, 2));
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let count = export::export(from, to, pool, |e| {
+        // stdout going away, e.g. piping into head, ends the export
+        if writeln!(out, "{}", serde_json::to_string(&e)?).is_err() {
+            process::exit(1);
+        }
+        Ok(())
+    })?;
+    eprintln!("exported {} events", count);
+    Ok(())
+}
+
+fn import_events(pool: &MyPool) -> Result<(), CreditError> {
+    let stdin = io::stdin();
+    let mut events = vec![];
+    for line in stdin.lock().lines() {
+        let line = line.map_err(|e| CreditError::InvalidImport(e.to_string()))?;
+        if !line.trim().is_empty() {
+            events.push(serde_json::from_str(&line)?);
+        }
+    }
+    let count = export::import(&events, pool)?;
+    eprintln!("imported {} events", count);
+    Ok(())
+}
+
+// signed with auth_key and valid for token_lifetime seconds
+fn token(args: &[String], config: &Config) -> Result<(), CreditError> {
+    let mut principal = match args.get(1).map(String::as_str) {
+        Some("admin") => Principal::admin(&arg::<String>(args, 2)),
+        Some("service") => Principal::service(&arg::<String>(args, 2), arg(args, 3)),
+        _ => usage()
+    };
+    if config.token_lifetime > 0 {
+        principal.exp = Some(Utc::now().timestamp() + config.token_lifetime);
+    }
+    println!("{}", Auth::new(config).issue(&principal)?);
+    Ok(())
+}
+
+fn webhook(args: &[String], pool: &MyPool) -> Result<(), CreditError> {
+    match args.get(1).map(String::as_str) {
+        Some("add") => {
+            let events = args.get(5..).unwrap_or(&[]);
+            println!("webhook {}", webhooks::register(arg(args, 2), &arg::<String>(args, 3), &arg::<String>(args, 4), events, pool)?)
+        },
+        Some("remove") => webhooks::unregister(arg(args, 2), pool)?,
+        Some("dead") => for d in webhooks::dead_letters(pool)? {
+            println!("{}\t{}\t{}\t{}\t{}", d.id, d.url, d.event_id, d.attempts, d.last_error.unwrap_or_default());
+        },
+        Some("retry") => webhooks::redeliver(arg(args, 2), pool)?,
+        _ => usage()
+    }
+    Ok(())
+}
+
+fn main() {
+    let config = Config::load(vec![]).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(2);
+    });
+    logging::init(&config);
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.is_empty() {
+        usage();
+    }
+
+    if args[0] == "token" {
+        if let Err(e) = token(&args, &config) {
+            eprintln!("{:?}", e);
+            process::exit(1);
+        }
+        return
+    }
+
+    let pool = eventstore::pool(&config);
+    let res = match args[0].as_str() {
+        "init" => init(&pool),
+        "inspect" => inspect(arg(&args, 1), &pool),
+        "events" => events(arg(&args, 1), &pool),
+        "replay" => replay(&pool),
+        "rebuild-snapshots" => rebuild_snapshots::<Contract>(&pool)
+            .and_then(|_| rebuild_snapshots::<Person>(&pool)),
+        "evict" => run(CreditCommand::EvictExpiredReservations(arg(&args, 2)), arg(&args, 1), &pool),
+        "add" => {
+            let credit_type = args.get(3).cloned().unwrap_or_else(default_credit_type);
+            run(CreditCommand::AddCredits(credit_type, arg(&args, 2)), arg(&args, 1), &pool)
+        },
+        "remove" => {
+            let credit_type = args.get(4).cloned().unwrap_or_else(default_credit_type);
+            run(CreditCommand::RemoveCredits(credit_type, arg(&args, 2), arg(&args, 3)), arg(&args, 1), &pool)
+        },
+        "refund" => run(CreditCommand::RefundSpend(arg::<Uuid>(&args, 2), arg(&args, 3)), arg(&args, 1), &pool),
+        "credit-limit" => run(CreditCommand::SetCreditLimit(arg(&args, 2), arg(&args, 3)), arg(&args, 1), &pool),
+        "freeze" => run(CreditCommand::FreezeAccount, arg(&args, 1), &pool),
+        "unfreeze" => run(CreditCommand::UnfreezeAccount, arg(&args, 1), &pool),
+        "close" => run(CreditCommand::CloseAccount, arg(&args, 1), &pool),
+        "quota" => {
+            let quota = if args.len() > 2 { Some(Quota { amount: arg(&args, 2), window: arg(&args, 3) }) } else { None };
+            run(CreditCommand::SetReservationQuota(quota), arg(&args, 1), &pool)
+        },
+        "alert" => {
+            let threshold = if args.len() > 3 { Some(arg(&args, 3)) } else { None };
+            run(CreditCommand::SetBalanceAlert(arg(&args, 2), threshold), arg(&args, 1), &pool)
+        },
+        "webhook" => webhook(&args, &pool),
+        "export" => export_events(&args, &pool),
+        "import" => import_events(&pool),
+        _ => usage()
+    };
+
+    if let Err(e) = res {
+        eprintln!("{:?}", e);
+        process::exit(1);
+    }
+}
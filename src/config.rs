@@ -0,0 +1,293 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use dotenv;
+use serde_json;
+use toml::{self, Value};
+use toml::value::Table;
+
+// file read when none is given, if it exists
+const DEFAULT_FILE: &str = "credits.toml";
+
+// environment variables starting with this override the setting named by the
+// rest of the variable, e.g. CREDITS_GRPC_ADDRESS sets grpc_address. Others
+// starting with it are left alone, they may be meant for something else.
+const ENV_PREFIX: &str = "CREDITS_";
+const ENV_FILE: &str = "CREDITS_CONFIG";
+
+// settings that had environment variables of their own before there was
+// a config file
+const ENV_ALIASES: &[(&str, &str)] = &[
+    ("DATABASE_URL", "database_url"),
+    ("RUST_LOG", "log_level"),
+    ("LOG_FORMAT", "log_format"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub database_url: Option<String>,
+    // disable, prefer or require
+    pub database_tls: String,
+    // PEM files of the CA the database's certificate is checked against, and
+    // of the certificate and key to present to it
+    pub database_tls_ca: Option<String>,
+    pub database_tls_cert: Option<String>,
+    pub database_tls_key: Option<String>,
+    pub grpc_address: String,
+    // completion queue threads serving gRPC calls
+    pub grpc_threads: usize,
+    // PEM files of the certificate chain and key to serve gRPC over TLS with
+    pub grpc_tls_cert: Option<String>,
+    pub grpc_tls_key: Option<String>,
+    // PEM file of the CA client certificates are checked against
+    pub grpc_tls_client_ca: Option<String>,
+    pub grpc_tls_require_client_cert: bool,
+    pub http_address: String,
+    pub http_workers: usize,
+    pub pool_max_size: u32,
+    // connections kept open when idle, the pool's max size if not set
+    pub pool_min_idle: Option<u32>,
+    // seconds to wait for a connection before giving up
+    pub pool_connection_timeout: u64,
+    // seconds before an idle connection is closed, never if 0
+    pub pool_idle_timeout: u64,
+    // seconds before a connection is replaced, never if 0
+    pub pool_max_lifetime: u64,
+    // events between snapshots of an aggregate
+    pub snapshot_interval: i64,
+    // filter in the same syntax as RUST_LOG
+    pub log_level: String,
+    // text or json
+    pub log_format: String,
+    // seconds to let calls in flight finish when asked to stop
+    pub shutdown_timeout: u64,
+    // secret signing bearer tokens, required unless auth is disabled
+    pub auth_key: Option<String>,
+    // lets every caller through as an admin without a token
+    pub auth_disabled: bool,
+    // seconds tokens issued by credits-admin are valid for, forever if 0
+    pub token_lifetime: i64,
+    // attempts at delivering an event to a webhook before it is dead-lettered
+    pub webhook_max_attempts: i32,
+    // seconds before the first retry, doubling with every attempt after
+    pub webhook_backoff: u64,
+    // seconds to wait for a webhook to connect and respond
+    pub webhook_timeout: u64
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            database_url: None,
+            database_tls: "disable".to_string(),
+            database_tls_ca: None,
+            database_tls_cert: None,
+            database_tls_key: None,
+            grpc_address: "127.0.0.1:5951".to_string(),
+            grpc_threads: 1,
+            grpc_tls_cert: None,
+            grpc_tls_key: None,
+            grpc_tls_client_ca: None,
+            grpc_tls_require_client_cert: false,
+            http_address: "127.0.0.1:5952".to_string(),
+            http_workers: 4,
+            pool_max_size: 10,
+            pool_min_idle: None,
+            pool_connection_timeout: 30,
+            pool_idle_timeout: 600,
+            pool_max_lifetime: 1800,
+            snapshot_interval: 1000,
+            log_level: "credits=info".to_string(),
+            log_format: "text".to_string(),
+            shutdown_timeout: 30,
+            auth_key: None,
+            auth_disabled: false,
+            token_lifetime: 30 * 24 * 3600,
+            webhook_max_attempts: 10,
+            webhook_backoff: 10,
+            webhook_timeout: 10
+        }
+    }
+}
+
+impl Config {
+    // Reads the config file, then the environment, then command line flags
+    // such as `--grpc-address 0.0.0.0:5951`, each overriding what came
+    // before. The file is named by --config or CREDITS_CONFIG.
+    pub fn load<I: IntoIterator<Item=String>>(args: I) -> Result<Config, String> {
+        dotenv::dotenv().ok();
+        let mut flags = parse_flags(args)?;
+
+        let file = match flags.iter().position(|&(ref k, _)| k == "config") {
+            Some(i) => Some(flags.remove(i).1),
+            None => env::var(ENV_FILE).ok()
+        };
+        let table = match file {
+            Some(path) => read(&path)?,
+            None if Path::new(DEFAULT_FILE).exists() => read(DEFAULT_FILE)?,
+            None => Table::new()
+        };
+
+        let mut settings = env_settings(env::vars());
+        settings.extend(flags);
+
+        build(table, settings)
+    }
+}
+
+// Settings given by environment variables, the old names coming first so
+// CREDITS_ ones win over them
+fn env_settings<I: IntoIterator<Item=(String, String)>>(vars: I) -> Vec<(String, String)> {
+    let vars: Vec<(String, String)> = vars.into_iter().collect();
+    let mut settings = vec![];
+    for (var, v) in vars.iter() {
+        if let Some(&(_, key)) = ENV_ALIASES.iter().find(|&&(alias, _)| alias == var) {
+            settings.push((key.to_string(), v.clone()));
+        }
+    }
+    let names = setting_names();
+    for (var, v) in vars.iter() {
+        if let Some(key) = var.strip_prefix(ENV_PREFIX).map(str::to_lowercase) {
+            if names.contains(&key) {
+                settings.push((key, v.clone()));
+            }
+        }
+    }
+    settings
+}
+
+fn read(path: &str) -> Result<Table, String> {
+    let s = fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path, e))?;
+    toml::from_str(&s).map_err(|e| format!("could not parse {}: {}", path, e))
+}
+
+// `--key value` and `--key=value` pairs, with dashes in keys made underscores
+fn parse_flags<I: IntoIterator<Item=String>>(args: I) -> Result<Vec<(String, String)>, String> {
+    let mut flags = vec![];
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            return Err(format!("unexpected argument {}", arg))
+        }
+        let mut parts = arg[2..].splitn(2, '=');
+        let key = parts.next().unwrap_or("").replace('-', "_");
+        let value = match parts.next() {
+            Some(v) => v.to_string(),
+            None => args.next().ok_or_else(|| format!("missing value for {}", arg))?
+        };
+        flags.push((key, value));
+    }
+    Ok(flags)
+}
+
+// Applies settings given as text on top of the file. Each is read as the
+// type of its field, so a secret made of digits stays text while `true` can
+// go into a flag.
+fn build(mut table: Table, settings: Vec<(String, String)>) -> Result<Config, String> {
+    for (key, v) in settings {
+        let value = typed(&key, v);
+        table.insert(key, value);
+    }
+    Value::Table(table).try_into().map_err(|e| format!("invalid configuration: {}", e))
+}
+
+// the setting as the first type its field takes, or as text to be reported
+// as invalid if none fits
+fn typed(key: &str, v: String) -> Value {
+    let fits = |value: &Value| {
+        let mut one = Table::new();
+        one.insert(key.to_string(), value.clone());
+        Value::Table(one).try_into::<Config>().is_ok()
+    };
+    let candidates = vec![
+        v.parse::<bool>().ok().map(Value::Boolean),
+        v.parse::<i64>().ok().map(Value::Integer),
+        Some(Value::String(v.clone())),
+    ];
+    candidates.into_iter().flatten().find(|c| fits(c)).unwrap_or(Value::String(v))
+}
+
+// Names of the settings, being the keys of the default config. JSON rather
+// than TOML, which leaves out the settings that are None.
+fn setting_names() -> Vec<String> {
+    match serde_json::to_value(Config::default()) {
+        Ok(serde_json::Value::Object(settings)) => settings.into_iter().map(|(k, _)| k).collect(),
+        _ => vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_falls_back_to_defaults() {
+        let c = build(Table::new(), vec![]).unwrap();
+        assert_eq!(c.grpc_address, "127.0.0.1:5951");
+        assert_eq!(c.snapshot_interval, 1000);
+    }
+
+    #[test]
+    fn later_settings_override_the_file() {
+        let table = toml::from_str("grpc_threads = 2\npool_max_size = 5").unwrap();
+        let flags = parse_flags(vec!["--grpc-threads".to_string(), "4".to_string(), "--log-level=debug".to_string()]).unwrap();
+        let c = build(table, flags).unwrap();
+        assert_eq!(c.grpc_threads, 4);
+        assert_eq!(c.pool_max_size, 5);
+        assert_eq!(c.log_level, "debug");
+    }
+
+    #[test]
+    fn it_reads_settings_as_their_fields_type() {
+        let flags = parse_flags(vec!["--auth-key=12345".to_string(), "--shutdown-timeout=5".to_string()]).unwrap();
+        let c = build(Table::new(), flags).unwrap();
+        assert_eq!(c.auth_key, Some("12345".to_string()));
+        assert_eq!(c.shutdown_timeout, 5);
+        build(Table::new(), vec![("shutdown_timeout".to_string(), "soon".to_string())])
+            .expect_err("should not accept text for a number");
+    }
+
+    #[test]
+    fn it_knows_every_setting() {
+        let names = setting_names();
+        assert!(names.contains(&"database_url".to_string()));
+        assert!(names.contains(&"webhook_timeout".to_string()));
+        assert!(!names.contains(&"foo".to_string()));
+    }
+
+    fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter().map(|&(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn it_leaves_other_environment_variables_alone() {
+        let settings = env_settings(vars(&[("CREDITS_NOT_A_SETTING", "1"), ("CREDITS_TOKEN_LIFETIME", "60"), ("HOME", "/root")]));
+        assert_eq!(settings, vars(&[("token_lifetime", "60")]));
+        assert_eq!(build(Table::new(), settings).unwrap().token_lifetime, 60);
+    }
+
+    #[test]
+    fn it_prefers_credits_variables_to_old_names() {
+        let settings = env_settings(vars(&[("CREDITS_DATABASE_URL", "postgres://new"), ("DATABASE_URL", "postgres://old")]));
+        assert_eq!(build(Table::new(), settings).unwrap().database_url, Some("postgres://new".to_string()));
+    }
+
+    #[test]
+    fn it_requires_client_certs_from_env_or_flags() {
+        assert!(!build(Table::new(), vec![]).unwrap().grpc_tls_require_client_cert);
+        let flags = parse_flags(vec!["--grpc-tls-require-client-cert".to_string(), "true".to_string()]).unwrap();
+        assert!(build(Table::new(), flags).unwrap().grpc_tls_require_client_cert);
+
+        let mut settings = env_settings(vars(&[("CREDITS_GRPC_TLS_REQUIRE_CLIENT_CERT", "true")]));
+        assert!(build(Table::new(), settings.clone()).unwrap().grpc_tls_require_client_cert);
+        settings.extend(parse_flags(vec!["--grpc-tls-require-client-cert=false".to_string()]).unwrap());
+        assert!(!build(Table::new(), settings).unwrap().grpc_tls_require_client_cert);
+    }
+
+    #[test]
+    fn it_rejects_unknown_settings() {
+        build(Table::new(), vec![("grpc_adress".to_string(), "x".to_string())])
+            .expect_err("should not accept a misspelled setting");
+    }
+}
@@ -26,6 +26,7 @@ pub struct AddCreditsCommand {
     // message fields
     pub account: i64,
     pub amount: i64,
+    pub credit_type: ::std::string::String,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     cached_size: ::protobuf::CachedSize,
@@ -65,6 +66,32 @@ impl AddCreditsCommand {
     pub fn get_amount(&self) -> i64 {
         self.amount
     }
+
+    // string credit_type = 3;
+
+    pub fn clear_credit_type(&mut self) {
+        self.credit_type.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_credit_type(&mut self, v: ::std::string::String) {
+        self.credit_type = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_credit_type(&mut self) -> &mut ::std::string::String {
+        &mut self.credit_type
+    }
+
+    // Take field
+    pub fn take_credit_type(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.credit_type, ::std::string::String::new())
+    }
+
+    pub fn get_credit_type(&self) -> &str {
+        &self.credit_type
+    }
 }
 
 impl ::protobuf::Message for AddCreditsCommand {
@@ -90,6 +117,9 @@ impl ::protobuf::Message for AddCreditsCommand {
                     let tmp = is.read_int64()?;
                     self.amount = tmp;
                 },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.credit_type)?;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -108,6 +138,9 @@ impl ::protobuf::Message for AddCreditsCommand {
         if self.amount != 0 {
             my_size += ::protobuf::rt::value_size(2, self.amount, ::protobuf::wire_format::WireTypeVarint);
         }
+        if !self.credit_type.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.credit_type);
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -120,6 +153,9 @@ impl ::protobuf::Message for AddCreditsCommand {
         if self.amount != 0 {
             os.write_int64(2, self.amount)?;
         }
+        if !self.credit_type.is_empty() {
+            os.write_string(3, &self.credit_type)?;
+        }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -172,6 +208,11 @@ impl ::protobuf::Message for AddCreditsCommand {
                     |m: &AddCreditsCommand| { &m.amount },
                     |m: &mut AddCreditsCommand| { &mut m.amount },
                 ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "credit_type",
+                    |m: &AddCreditsCommand| { &m.credit_type },
+                    |m: &mut AddCreditsCommand| { &mut m.credit_type },
+                ));
                 ::protobuf::reflect::MessageDescriptor::new::<AddCreditsCommand>(
                     "AddCreditsCommand",
                     fields,
@@ -196,6 +237,7 @@ impl ::protobuf::Clear for AddCreditsCommand {
     fn clear(&mut self) {
         self.clear_account();
         self.clear_amount();
+        self.clear_credit_type();
         self.unknown_fields.clear();
     }
 }
@@ -369,36 +411,122 @@ impl ::protobuf::reflect::ProtobufValue for AddCreditsResponse {
 }
 
 #[derive(PartialEq,Clone,Default)]
-pub struct AccountStatusRequest {
+pub struct TransferCreditsCommand {
     // message fields
-    pub account: i64,
+    pub source: i64,
+    pub target: i64,
+    pub amount: i64,
+    pub credit_type: ::std::string::String,
+    pub id: ::std::string::String,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     cached_size: ::protobuf::CachedSize,
 }
 
-impl AccountStatusRequest {
-    pub fn new() -> AccountStatusRequest {
+impl TransferCreditsCommand {
+    pub fn new() -> TransferCreditsCommand {
         ::std::default::Default::default()
     }
 
-    // int64 account = 1;
+    // int64 source = 1;
 
-    pub fn clear_account(&mut self) {
-        self.account = 0;
+    pub fn clear_source(&mut self) {
+        self.source = 0;
     }
 
     // Param is passed by value, moved
-    pub fn set_account(&mut self, v: i64) {
-        self.account = v;
+    pub fn set_source(&mut self, v: i64) {
+        self.source = v;
     }
 
-    pub fn get_account(&self) -> i64 {
-        self.account
+    pub fn get_source(&self) -> i64 {
+        self.source
+    }
+
+    // int64 target = 2;
+
+    pub fn clear_target(&mut self) {
+        self.target = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_target(&mut self, v: i64) {
+        self.target = v;
+    }
+
+    pub fn get_target(&self) -> i64 {
+        self.target
+    }
+
+    // int64 amount = 3;
+
+    pub fn clear_amount(&mut self) {
+        self.amount = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_amount(&mut self, v: i64) {
+        self.amount = v;
+    }
+
+    pub fn get_amount(&self) -> i64 {
+        self.amount
+    }
+
+    // string credit_type = 4;
+
+    pub fn clear_credit_type(&mut self) {
+        self.credit_type.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_credit_type(&mut self, v: ::std::string::String) {
+        self.credit_type = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_credit_type(&mut self) -> &mut ::std::string::String {
+        &mut self.credit_type
+    }
+
+    // Take field
+    pub fn take_credit_type(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.credit_type, ::std::string::String::new())
+    }
+
+    pub fn get_credit_type(&self) -> &str {
+        &self.credit_type
+    }
+
+    // string id = 5;
+
+    pub fn clear_id(&mut self) {
+        self.id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_id(&mut self, v: ::std::string::String) {
+        self.id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_id(&mut self) -> &mut ::std::string::String {
+        &mut self.id
+    }
+
+    // Take field
+    pub fn take_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.id, ::std::string::String::new())
+    }
+
+    pub fn get_id(&self) -> &str {
+        &self.id
     }
 }
 
-impl ::protobuf::Message for AccountStatusRequest {
+impl ::protobuf::Message for TransferCreditsCommand {
     fn is_initialized(&self) -> bool {
         true
     }
@@ -412,7 +540,27 @@ impl ::protobuf::Message for AccountStatusRequest {
                         return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
                     }
                     let tmp = is.read_int64()?;
-                    self.account = tmp;
+                    self.source = tmp;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.target = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.amount = tmp;
+                },
+                4 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.credit_type)?;
+                },
+                5 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.id)?;
                 },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
@@ -426,8 +574,20 @@ impl ::protobuf::Message for AccountStatusRequest {
     #[allow(unused_variables)]
     fn compute_size(&self) -> u32 {
         let mut my_size = 0;
-        if self.account != 0 {
-            my_size += ::protobuf::rt::value_size(1, self.account, ::protobuf::wire_format::WireTypeVarint);
+        if self.source != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.source, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.target != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.target, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.amount != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.amount, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.credit_type.is_empty() {
+            my_size += ::protobuf::rt::string_size(4, &self.credit_type);
+        }
+        if !self.id.is_empty() {
+            my_size += ::protobuf::rt::string_size(5, &self.id);
         }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
@@ -435,8 +595,20 @@ impl ::protobuf::Message for AccountStatusRequest {
     }
 
     fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
-        if self.account != 0 {
-            os.write_int64(1, self.account)?;
+        if self.source != 0 {
+            os.write_int64(1, self.source)?;
+        }
+        if self.target != 0 {
+            os.write_int64(2, self.target)?;
+        }
+        if self.amount != 0 {
+            os.write_int64(3, self.amount)?;
+        }
+        if !self.credit_type.is_empty() {
+            os.write_string(4, &self.credit_type)?;
+        }
+        if !self.id.is_empty() {
+            os.write_string(5, &self.id)?;
         }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
@@ -468,8 +640,8 @@ impl ::protobuf::Message for AccountStatusRequest {
         Self::descriptor_static()
     }
 
-    fn new() -> AccountStatusRequest {
-        AccountStatusRequest::new()
+    fn new() -> TransferCreditsCommand {
+        TransferCreditsCommand::new()
     }
 
     fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
@@ -481,12 +653,32 @@ impl ::protobuf::Message for AccountStatusRequest {
             descriptor.get(|| {
                 let mut fields = ::std::vec::Vec::new();
                 fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
-                    "account",
-                    |m: &AccountStatusRequest| { &m.account },
-                    |m: &mut AccountStatusRequest| { &mut m.account },
+                    "source",
+                    |m: &TransferCreditsCommand| { &m.source },
+                    |m: &mut TransferCreditsCommand| { &mut m.source },
                 ));
-                ::protobuf::reflect::MessageDescriptor::new::<AccountStatusRequest>(
-                    "AccountStatusRequest",
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "target",
+                    |m: &TransferCreditsCommand| { &m.target },
+                    |m: &mut TransferCreditsCommand| { &mut m.target },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "amount",
+                    |m: &TransferCreditsCommand| { &m.amount },
+                    |m: &mut TransferCreditsCommand| { &mut m.amount },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "credit_type",
+                    |m: &TransferCreditsCommand| { &m.credit_type },
+                    |m: &mut TransferCreditsCommand| { &mut m.credit_type },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "id",
+                    |m: &TransferCreditsCommand| { &m.id },
+                    |m: &mut TransferCreditsCommand| { &mut m.id },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<TransferCreditsCommand>(
+                    "TransferCreditsCommand",
                     fields,
                     file_descriptor_proto()
                 )
@@ -494,67 +686,109 @@ impl ::protobuf::Message for AccountStatusRequest {
         }
     }
 
-    fn default_instance() -> &'static AccountStatusRequest {
-        static mut instance: ::protobuf::lazy::Lazy<AccountStatusRequest> = ::protobuf::lazy::Lazy {
+    fn default_instance() -> &'static TransferCreditsCommand {
+        static mut instance: ::protobuf::lazy::Lazy<TransferCreditsCommand> = ::protobuf::lazy::Lazy {
             lock: ::protobuf::lazy::ONCE_INIT,
-            ptr: 0 as *const AccountStatusRequest,
+            ptr: 0 as *const TransferCreditsCommand,
         };
         unsafe {
-            instance.get(AccountStatusRequest::new)
+            instance.get(TransferCreditsCommand::new)
         }
     }
 }
 
-impl ::protobuf::Clear for AccountStatusRequest {
+impl ::protobuf::Clear for TransferCreditsCommand {
     fn clear(&mut self) {
-        self.clear_account();
+        self.clear_source();
+        self.clear_target();
+        self.clear_amount();
+        self.clear_credit_type();
+        self.clear_id();
         self.unknown_fields.clear();
     }
 }
 
-impl ::std::fmt::Debug for AccountStatusRequest {
+impl ::std::fmt::Debug for TransferCreditsCommand {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         ::protobuf::text_format::fmt(self, f)
     }
 }
 
-impl ::protobuf::reflect::ProtobufValue for AccountStatusRequest {
+impl ::protobuf::reflect::ProtobufValue for TransferCreditsCommand {
     fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
         ::protobuf::reflect::ProtobufValueRef::Message(self)
     }
 }
 
 #[derive(PartialEq,Clone,Default)]
-pub struct AccountStatus {
+pub struct TransferCreditsResponse {
     // message fields
-    pub amount: i64,
+    pub id: ::std::string::String,
+    pub state: ::std::string::String,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     cached_size: ::protobuf::CachedSize,
 }
 
-impl AccountStatus {
-    pub fn new() -> AccountStatus {
+impl TransferCreditsResponse {
+    pub fn new() -> TransferCreditsResponse {
         ::std::default::Default::default()
     }
 
-    // int64 amount = 1;
+    // string id = 1;
 
-    pub fn clear_amount(&mut self) {
-        self.amount = 0;
+    pub fn clear_id(&mut self) {
+        self.id.clear();
     }
 
     // Param is passed by value, moved
-    pub fn set_amount(&mut self, v: i64) {
-        self.amount = v;
+    pub fn set_id(&mut self, v: ::std::string::String) {
+        self.id = v;
     }
 
-    pub fn get_amount(&self) -> i64 {
-        self.amount
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_id(&mut self) -> &mut ::std::string::String {
+        &mut self.id
+    }
+
+    // Take field
+    pub fn take_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.id, ::std::string::String::new())
+    }
+
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    // string state = 2;
+
+    pub fn clear_state(&mut self) {
+        self.state.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_state(&mut self, v: ::std::string::String) {
+        self.state = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_state(&mut self) -> &mut ::std::string::String {
+        &mut self.state
+    }
+
+    // Take field
+    pub fn take_state(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.state, ::std::string::String::new())
+    }
+
+    pub fn get_state(&self) -> &str {
+        &self.state
     }
 }
 
-impl ::protobuf::Message for AccountStatus {
+impl ::protobuf::Message for TransferCreditsResponse {
     fn is_initialized(&self) -> bool {
         true
     }
@@ -564,11 +798,10 @@ impl ::protobuf::Message for AccountStatus {
             let (field_number, wire_type) = is.read_tag_unpack()?;
             match field_number {
                 1 => {
-                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
-                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
-                    }
-                    let tmp = is.read_int64()?;
-                    self.amount = tmp;
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.id)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.state)?;
                 },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
@@ -582,8 +815,11 @@ impl ::protobuf::Message for AccountStatus {
     #[allow(unused_variables)]
     fn compute_size(&self) -> u32 {
         let mut my_size = 0;
-        if self.amount != 0 {
-            my_size += ::protobuf::rt::value_size(1, self.amount, ::protobuf::wire_format::WireTypeVarint);
+        if !self.id.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.id);
+        }
+        if !self.state.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.state);
         }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
@@ -591,8 +827,11 @@ impl ::protobuf::Message for AccountStatus {
     }
 
     fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
-        if self.amount != 0 {
-            os.write_int64(1, self.amount)?;
+        if !self.id.is_empty() {
+            os.write_string(1, &self.id)?;
+        }
+        if !self.state.is_empty() {
+            os.write_string(2, &self.state)?;
         }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
@@ -624,8 +863,8 @@ impl ::protobuf::Message for AccountStatus {
         Self::descriptor_static()
     }
 
-    fn new() -> AccountStatus {
-        AccountStatus::new()
+    fn new() -> TransferCreditsResponse {
+        TransferCreditsResponse::new()
     }
 
     fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
@@ -636,13 +875,18 @@ impl ::protobuf::Message for AccountStatus {
         unsafe {
             descriptor.get(|| {
                 let mut fields = ::std::vec::Vec::new();
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
-                    "amount",
-                    |m: &AccountStatus| { &m.amount },
-                    |m: &mut AccountStatus| { &mut m.amount },
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "id",
+                    |m: &TransferCreditsResponse| { &m.id },
+                    |m: &mut TransferCreditsResponse| { &mut m.id },
                 ));
-                ::protobuf::reflect::MessageDescriptor::new::<AccountStatus>(
-                    "AccountStatus",
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "state",
+                    |m: &TransferCreditsResponse| { &m.state },
+                    |m: &mut TransferCreditsResponse| { &mut m.state },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<TransferCreditsResponse>(
+                    "TransferCreditsResponse",
                     fields,
                     file_descriptor_proto()
                 )
@@ -650,74 +894,2797 @@ impl ::protobuf::Message for AccountStatus {
         }
     }
 
-    fn default_instance() -> &'static AccountStatus {
-        static mut instance: ::protobuf::lazy::Lazy<AccountStatus> = ::protobuf::lazy::Lazy {
+    fn default_instance() -> &'static TransferCreditsResponse {
+        static mut instance: ::protobuf::lazy::Lazy<TransferCreditsResponse> = ::protobuf::lazy::Lazy {
             lock: ::protobuf::lazy::ONCE_INIT,
-            ptr: 0 as *const AccountStatus,
+            ptr: 0 as *const TransferCreditsResponse,
         };
         unsafe {
-            instance.get(AccountStatus::new)
+            instance.get(TransferCreditsResponse::new)
         }
     }
 }
 
-impl ::protobuf::Clear for AccountStatus {
+impl ::protobuf::Clear for TransferCreditsResponse {
     fn clear(&mut self) {
-        self.clear_amount();
+        self.clear_id();
+        self.clear_state();
         self.unknown_fields.clear();
     }
 }
 
-impl ::std::fmt::Debug for AccountStatus {
+impl ::std::fmt::Debug for TransferCreditsResponse {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         ::protobuf::text_format::fmt(self, f)
     }
 }
 
-impl ::protobuf::reflect::ProtobufValue for AccountStatus {
+impl ::protobuf::reflect::ProtobufValue for TransferCreditsResponse {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct ReserveCreditsCommand {
+    // message fields
+    pub account: i64,
+    pub id: ::std::string::String,
+    pub amount: i64,
+    pub credit_type: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+impl ReserveCreditsCommand {
+    pub fn new() -> ReserveCreditsCommand {
+        ::std::default::Default::default()
+    }
+
+    // int64 account = 1;
+
+    pub fn clear_account(&mut self) {
+        self.account = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_account(&mut self, v: i64) {
+        self.account = v;
+    }
+
+    pub fn get_account(&self) -> i64 {
+        self.account
+    }
+
+    // string id = 2;
+
+    pub fn clear_id(&mut self) {
+        self.id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_id(&mut self, v: ::std::string::String) {
+        self.id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_id(&mut self) -> &mut ::std::string::String {
+        &mut self.id
+    }
+
+    // Take field
+    pub fn take_id(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.id, ::std::string::String::new())
+    }
+
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    // int64 amount = 3;
+
+    pub fn clear_amount(&mut self) {
+        self.amount = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_amount(&mut self, v: i64) {
+        self.amount = v;
+    }
+
+    pub fn get_amount(&self) -> i64 {
+        self.amount
+    }
+
+    // string credit_type = 4;
+
+    pub fn clear_credit_type(&mut self) {
+        self.credit_type.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_credit_type(&mut self, v: ::std::string::String) {
+        self.credit_type = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_credit_type(&mut self) -> &mut ::std::string::String {
+        &mut self.credit_type
+    }
+
+    // Take field
+    pub fn take_credit_type(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.credit_type, ::std::string::String::new())
+    }
+
+    pub fn get_credit_type(&self) -> &str {
+        &self.credit_type
+    }
+}
+
+impl ::protobuf::Message for ReserveCreditsCommand {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.account = tmp;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.id)?;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.amount = tmp;
+                },
+                4 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.credit_type)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.account != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.account, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.id.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.id);
+        }
+        if self.amount != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.amount, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.credit_type.is_empty() {
+            my_size += ::protobuf::rt::string_size(4, &self.credit_type);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        if self.account != 0 {
+            os.write_int64(1, self.account)?;
+        }
+        if !self.id.is_empty() {
+            os.write_string(2, &self.id)?;
+        }
+        if self.amount != 0 {
+            os.write_int64(3, self.amount)?;
+        }
+        if !self.credit_type.is_empty() {
+            os.write_string(4, &self.credit_type)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> ReserveCreditsCommand {
+        ReserveCreditsCommand::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "account",
+                    |m: &ReserveCreditsCommand| { &m.account },
+                    |m: &mut ReserveCreditsCommand| { &mut m.account },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "id",
+                    |m: &ReserveCreditsCommand| { &m.id },
+                    |m: &mut ReserveCreditsCommand| { &mut m.id },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "amount",
+                    |m: &ReserveCreditsCommand| { &m.amount },
+                    |m: &mut ReserveCreditsCommand| { &mut m.amount },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "credit_type",
+                    |m: &ReserveCreditsCommand| { &m.credit_type },
+                    |m: &mut ReserveCreditsCommand| { &mut m.credit_type },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<ReserveCreditsCommand>(
+                    "ReserveCreditsCommand",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static ReserveCreditsCommand {
+        static mut instance: ::protobuf::lazy::Lazy<ReserveCreditsCommand> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ReserveCreditsCommand,
+        };
+        unsafe {
+            instance.get(ReserveCreditsCommand::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for ReserveCreditsCommand {
+    fn clear(&mut self) {
+        self.clear_account();
+        self.clear_id();
+        self.clear_amount();
+        self.clear_credit_type();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for ReserveCreditsCommand {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for ReserveCreditsCommand {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct ReserveCreditsResponse {
+    // message fields
+    pub new_amount: i64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+impl ReserveCreditsResponse {
+    pub fn new() -> ReserveCreditsResponse {
+        ::std::default::Default::default()
+    }
+
+    // int64 new_amount = 1;
+
+    pub fn clear_new_amount(&mut self) {
+        self.new_amount = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_new_amount(&mut self, v: i64) {
+        self.new_amount = v;
+    }
+
+    pub fn get_new_amount(&self) -> i64 {
+        self.new_amount
+    }
+}
+
+impl ::protobuf::Message for ReserveCreditsResponse {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.new_amount = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.new_amount != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.new_amount, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        if self.new_amount != 0 {
+            os.write_int64(1, self.new_amount)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> ReserveCreditsResponse {
+        ReserveCreditsResponse::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "new_amount",
+                    |m: &ReserveCreditsResponse| { &m.new_amount },
+                    |m: &mut ReserveCreditsResponse| { &mut m.new_amount },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<ReserveCreditsResponse>(
+                    "ReserveCreditsResponse",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static ReserveCreditsResponse {
+        static mut instance: ::protobuf::lazy::Lazy<ReserveCreditsResponse> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ReserveCreditsResponse,
+        };
+        unsafe {
+            instance.get(ReserveCreditsResponse::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for ReserveCreditsResponse {
+    fn clear(&mut self) {
+        self.clear_new_amount();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for ReserveCreditsResponse {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for ReserveCreditsResponse {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct AttachContractCommand {
+    // message fields
+    pub customer: i64,
+    pub account: i64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+impl AttachContractCommand {
+    pub fn new() -> AttachContractCommand {
+        ::std::default::Default::default()
+    }
+
+    // int64 customer = 1;
+
+    pub fn clear_customer(&mut self) {
+        self.customer = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_customer(&mut self, v: i64) {
+        self.customer = v;
+    }
+
+    pub fn get_customer(&self) -> i64 {
+        self.customer
+    }
+
+    // int64 account = 2;
+
+    pub fn clear_account(&mut self) {
+        self.account = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_account(&mut self, v: i64) {
+        self.account = v;
+    }
+
+    pub fn get_account(&self) -> i64 {
+        self.account
+    }
+}
+
+impl ::protobuf::Message for AttachContractCommand {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.customer = tmp;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.account = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.customer != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.customer, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.account != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.account, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        if self.customer != 0 {
+            os.write_int64(1, self.customer)?;
+        }
+        if self.account != 0 {
+            os.write_int64(2, self.account)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> AttachContractCommand {
+        AttachContractCommand::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "customer",
+                    |m: &AttachContractCommand| { &m.customer },
+                    |m: &mut AttachContractCommand| { &mut m.customer },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "account",
+                    |m: &AttachContractCommand| { &m.account },
+                    |m: &mut AttachContractCommand| { &mut m.account },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<AttachContractCommand>(
+                    "AttachContractCommand",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static AttachContractCommand {
+        static mut instance: ::protobuf::lazy::Lazy<AttachContractCommand> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const AttachContractCommand,
+        };
+        unsafe {
+            instance.get(AttachContractCommand::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for AttachContractCommand {
+    fn clear(&mut self) {
+        self.clear_customer();
+        self.clear_account();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for AttachContractCommand {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for AttachContractCommand {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct AttachContractResponse {
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+impl AttachContractResponse {
+    pub fn new() -> AttachContractResponse {
+        ::std::default::Default::default()
+    }
+}
+
+impl ::protobuf::Message for AttachContractResponse {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> AttachContractResponse {
+        AttachContractResponse::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let fields = ::std::vec::Vec::new();
+                ::protobuf::reflect::MessageDescriptor::new::<AttachContractResponse>(
+                    "AttachContractResponse",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static AttachContractResponse {
+        static mut instance: ::protobuf::lazy::Lazy<AttachContractResponse> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const AttachContractResponse,
+        };
+        unsafe {
+            instance.get(AttachContractResponse::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for AttachContractResponse {
+    fn clear(&mut self) {
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for AttachContractResponse {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for AttachContractResponse {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct CustomerStatusRequest {
+    // message fields
+    pub customer: i64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+impl CustomerStatusRequest {
+    pub fn new() -> CustomerStatusRequest {
+        ::std::default::Default::default()
+    }
+
+    // int64 customer = 1;
+
+    pub fn clear_customer(&mut self) {
+        self.customer = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_customer(&mut self, v: i64) {
+        self.customer = v;
+    }
+
+    pub fn get_customer(&self) -> i64 {
+        self.customer
+    }
+}
+
+impl ::protobuf::Message for CustomerStatusRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.customer = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.customer != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.customer, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        if self.customer != 0 {
+            os.write_int64(1, self.customer)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> CustomerStatusRequest {
+        CustomerStatusRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "customer",
+                    |m: &CustomerStatusRequest| { &m.customer },
+                    |m: &mut CustomerStatusRequest| { &mut m.customer },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<CustomerStatusRequest>(
+                    "CustomerStatusRequest",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static CustomerStatusRequest {
+        static mut instance: ::protobuf::lazy::Lazy<CustomerStatusRequest> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const CustomerStatusRequest,
+        };
+        unsafe {
+            instance.get(CustomerStatusRequest::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for CustomerStatusRequest {
+    fn clear(&mut self) {
+        self.clear_customer();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for CustomerStatusRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for CustomerStatusRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct CustomerStatus {
+    // message fields
+    pub available: i64,
+    pub reserved: i64,
+    pub balances: ::protobuf::RepeatedField<CustomerBalance>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+impl CustomerStatus {
+    pub fn new() -> CustomerStatus {
+        ::std::default::Default::default()
+    }
+
+    // int64 available = 1;
+
+    pub fn clear_available(&mut self) {
+        self.available = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_available(&mut self, v: i64) {
+        self.available = v;
+    }
+
+    pub fn get_available(&self) -> i64 {
+        self.available
+    }
+
+    // int64 reserved = 2;
+
+    pub fn clear_reserved(&mut self) {
+        self.reserved = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_reserved(&mut self, v: i64) {
+        self.reserved = v;
+    }
+
+    pub fn get_reserved(&self) -> i64 {
+        self.reserved
+    }
+
+    // repeated .CustomerBalance balances = 3;
+
+    pub fn clear_balances(&mut self) {
+        self.balances.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_balances(&mut self, v: ::protobuf::RepeatedField<CustomerBalance>) {
+        self.balances = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_balances(&mut self) -> &mut ::protobuf::RepeatedField<CustomerBalance> {
+        &mut self.balances
+    }
+
+    // Take field
+    pub fn take_balances(&mut self) -> ::protobuf::RepeatedField<CustomerBalance> {
+        ::std::mem::replace(&mut self.balances, ::protobuf::RepeatedField::new())
+    }
+
+    pub fn get_balances(&self) -> &[CustomerBalance] {
+        &self.balances
+    }
+}
+
+impl ::protobuf::Message for CustomerStatus {
+    fn is_initialized(&self) -> bool {
+        for v in &self.balances {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.available = tmp;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.reserved = tmp;
+                },
+                3 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.balances)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.available != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.available, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.reserved != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.reserved, ::protobuf::wire_format::WireTypeVarint);
+        }
+        for value in &self.balances {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        if self.available != 0 {
+            os.write_int64(1, self.available)?;
+        }
+        if self.reserved != 0 {
+            os.write_int64(2, self.reserved)?;
+        }
+        for v in &self.balances {
+            os.write_tag(3, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> CustomerStatus {
+        CustomerStatus::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "available",
+                    |m: &CustomerStatus| { &m.available },
+                    |m: &mut CustomerStatus| { &mut m.available },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "reserved",
+                    |m: &CustomerStatus| { &m.reserved },
+                    |m: &mut CustomerStatus| { &mut m.reserved },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<CustomerBalance>>(
+                    "balances",
+                    |m: &CustomerStatus| { &m.balances },
+                    |m: &mut CustomerStatus| { &mut m.balances },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<CustomerStatus>(
+                    "CustomerStatus",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static CustomerStatus {
+        static mut instance: ::protobuf::lazy::Lazy<CustomerStatus> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const CustomerStatus,
+        };
+        unsafe {
+            instance.get(CustomerStatus::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for CustomerStatus {
+    fn clear(&mut self) {
+        self.clear_available();
+        self.clear_reserved();
+        self.clear_balances();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for CustomerStatus {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for CustomerStatus {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct CustomerBalance {
+    // message fields
+    pub credit_type: ::std::string::String,
+    pub available: i64,
+    pub reserved: i64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+impl CustomerBalance {
+    pub fn new() -> CustomerBalance {
+        ::std::default::Default::default()
+    }
+
+    // string credit_type = 1;
+
+    pub fn clear_credit_type(&mut self) {
+        self.credit_type.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_credit_type(&mut self, v: ::std::string::String) {
+        self.credit_type = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_credit_type(&mut self) -> &mut ::std::string::String {
+        &mut self.credit_type
+    }
+
+    // Take field
+    pub fn take_credit_type(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.credit_type, ::std::string::String::new())
+    }
+
+    pub fn get_credit_type(&self) -> &str {
+        &self.credit_type
+    }
+
+    // int64 available = 2;
+
+    pub fn clear_available(&mut self) {
+        self.available = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_available(&mut self, v: i64) {
+        self.available = v;
+    }
+
+    pub fn get_available(&self) -> i64 {
+        self.available
+    }
+
+    // int64 reserved = 3;
+
+    pub fn clear_reserved(&mut self) {
+        self.reserved = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_reserved(&mut self, v: i64) {
+        self.reserved = v;
+    }
+
+    pub fn get_reserved(&self) -> i64 {
+        self.reserved
+    }
+}
+
+impl ::protobuf::Message for CustomerBalance {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.credit_type)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.available = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.reserved = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.credit_type.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.credit_type);
+        }
+        if self.available != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.available, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.reserved != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.reserved, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        if !self.credit_type.is_empty() {
+            os.write_string(1, &self.credit_type)?;
+        }
+        if self.available != 0 {
+            os.write_int64(2, self.available)?;
+        }
+        if self.reserved != 0 {
+            os.write_int64(3, self.reserved)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> CustomerBalance {
+        CustomerBalance::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "credit_type",
+                    |m: &CustomerBalance| { &m.credit_type },
+                    |m: &mut CustomerBalance| { &mut m.credit_type },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "available",
+                    |m: &CustomerBalance| { &m.available },
+                    |m: &mut CustomerBalance| { &mut m.available },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "reserved",
+                    |m: &CustomerBalance| { &m.reserved },
+                    |m: &mut CustomerBalance| { &mut m.reserved },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<CustomerBalance>(
+                    "CustomerBalance",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static CustomerBalance {
+        static mut instance: ::protobuf::lazy::Lazy<CustomerBalance> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const CustomerBalance,
+        };
+        unsafe {
+            instance.get(CustomerBalance::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for CustomerBalance {
+    fn clear(&mut self) {
+        self.clear_credit_type();
+        self.clear_available();
+        self.clear_reserved();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for CustomerBalance {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for CustomerBalance {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct AccountStatusRequest {
+    // message fields
+    pub account: i64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+impl AccountStatusRequest {
+    pub fn new() -> AccountStatusRequest {
+        ::std::default::Default::default()
+    }
+
+    // int64 account = 1;
+
+    pub fn clear_account(&mut self) {
+        self.account = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_account(&mut self, v: i64) {
+        self.account = v;
+    }
+
+    pub fn get_account(&self) -> i64 {
+        self.account
+    }
+}
+
+impl ::protobuf::Message for AccountStatusRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.account = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.account != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.account, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        if self.account != 0 {
+            os.write_int64(1, self.account)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> AccountStatusRequest {
+        AccountStatusRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "account",
+                    |m: &AccountStatusRequest| { &m.account },
+                    |m: &mut AccountStatusRequest| { &mut m.account },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<AccountStatusRequest>(
+                    "AccountStatusRequest",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static AccountStatusRequest {
+        static mut instance: ::protobuf::lazy::Lazy<AccountStatusRequest> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const AccountStatusRequest,
+        };
+        unsafe {
+            instance.get(AccountStatusRequest::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for AccountStatusRequest {
+    fn clear(&mut self) {
+        self.clear_account();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for AccountStatusRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for AccountStatusRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct AccountStatus {
+    // message fields
+    pub amount: i64,
+    pub balances: ::protobuf::RepeatedField<CreditBalance>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+impl AccountStatus {
+    pub fn new() -> AccountStatus {
+        ::std::default::Default::default()
+    }
+
+    // int64 amount = 1;
+
+    pub fn clear_amount(&mut self) {
+        self.amount = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_amount(&mut self, v: i64) {
+        self.amount = v;
+    }
+
+    pub fn get_amount(&self) -> i64 {
+        self.amount
+    }
+
+    // repeated .CreditBalance balances = 2;
+
+    pub fn clear_balances(&mut self) {
+        self.balances.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_balances(&mut self, v: ::protobuf::RepeatedField<CreditBalance>) {
+        self.balances = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_balances(&mut self) -> &mut ::protobuf::RepeatedField<CreditBalance> {
+        &mut self.balances
+    }
+
+    // Take field
+    pub fn take_balances(&mut self) -> ::protobuf::RepeatedField<CreditBalance> {
+        ::std::mem::replace(&mut self.balances, ::protobuf::RepeatedField::new())
+    }
+
+    pub fn get_balances(&self) -> &[CreditBalance] {
+        &self.balances
+    }
+}
+
+impl ::protobuf::Message for AccountStatus {
+    fn is_initialized(&self) -> bool {
+        for v in &self.balances {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.amount = tmp;
+                },
+                2 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.balances)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.amount != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.amount, ::protobuf::wire_format::WireTypeVarint);
+        }
+        for value in &self.balances {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        if self.amount != 0 {
+            os.write_int64(1, self.amount)?;
+        }
+        for v in &self.balances {
+            os.write_tag(2, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> AccountStatus {
+        AccountStatus::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "amount",
+                    |m: &AccountStatus| { &m.amount },
+                    |m: &mut AccountStatus| { &mut m.amount },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<CreditBalance>>(
+                    "balances",
+                    |m: &AccountStatus| { &m.balances },
+                    |m: &mut AccountStatus| { &mut m.balances },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<AccountStatus>(
+                    "AccountStatus",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static AccountStatus {
+        static mut instance: ::protobuf::lazy::Lazy<AccountStatus> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const AccountStatus,
+        };
+        unsafe {
+            instance.get(AccountStatus::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for AccountStatus {
+    fn clear(&mut self) {
+        self.clear_amount();
+        self.clear_balances();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for AccountStatus {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for AccountStatus {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct CreditBalance {
+    // message fields
+    pub credit_type: ::std::string::String,
+    pub amount: i64,
+    pub spent: i64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+impl CreditBalance {
+    pub fn new() -> CreditBalance {
+        ::std::default::Default::default()
+    }
+
+    // string credit_type = 1;
+
+    pub fn clear_credit_type(&mut self) {
+        self.credit_type.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_credit_type(&mut self, v: ::std::string::String) {
+        self.credit_type = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_credit_type(&mut self) -> &mut ::std::string::String {
+        &mut self.credit_type
+    }
+
+    // Take field
+    pub fn take_credit_type(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.credit_type, ::std::string::String::new())
+    }
+
+    pub fn get_credit_type(&self) -> &str {
+        &self.credit_type
+    }
+
+    // int64 amount = 2;
+
+    pub fn clear_amount(&mut self) {
+        self.amount = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_amount(&mut self, v: i64) {
+        self.amount = v;
+    }
+
+    pub fn get_amount(&self) -> i64 {
+        self.amount
+    }
+
+    // int64 spent = 3;
+
+    pub fn clear_spent(&mut self) {
+        self.spent = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_spent(&mut self, v: i64) {
+        self.spent = v;
+    }
+
+    pub fn get_spent(&self) -> i64 {
+        self.spent
+    }
+}
+
+impl ::protobuf::Message for CreditBalance {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.credit_type)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.amount = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.spent = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.credit_type.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.credit_type);
+        }
+        if self.amount != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.amount, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.spent != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.spent, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        if !self.credit_type.is_empty() {
+            os.write_string(1, &self.credit_type)?;
+        }
+        if self.amount != 0 {
+            os.write_int64(2, self.amount)?;
+        }
+        if self.spent != 0 {
+            os.write_int64(3, self.spent)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> CreditBalance {
+        CreditBalance::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "credit_type",
+                    |m: &CreditBalance| { &m.credit_type },
+                    |m: &mut CreditBalance| { &mut m.credit_type },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "amount",
+                    |m: &CreditBalance| { &m.amount },
+                    |m: &mut CreditBalance| { &mut m.amount },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "spent",
+                    |m: &CreditBalance| { &m.spent },
+                    |m: &mut CreditBalance| { &mut m.spent },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<CreditBalance>(
+                    "CreditBalance",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static CreditBalance {
+        static mut instance: ::protobuf::lazy::Lazy<CreditBalance> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const CreditBalance,
+        };
+        unsafe {
+            instance.get(CreditBalance::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for CreditBalance {
+    fn clear(&mut self) {
+        self.clear_credit_type();
+        self.clear_amount();
+        self.clear_spent();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for CreditBalance {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for CreditBalance {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct TrialBalanceRequest {
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+impl TrialBalanceRequest {
+    pub fn new() -> TrialBalanceRequest {
+        ::std::default::Default::default()
+    }
+}
+
+impl ::protobuf::Message for TrialBalanceRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> TrialBalanceRequest {
+        TrialBalanceRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let fields = ::std::vec::Vec::new();
+                ::protobuf::reflect::MessageDescriptor::new::<TrialBalanceRequest>(
+                    "TrialBalanceRequest",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static TrialBalanceRequest {
+        static mut instance: ::protobuf::lazy::Lazy<TrialBalanceRequest> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const TrialBalanceRequest,
+        };
+        unsafe {
+            instance.get(TrialBalanceRequest::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for TrialBalanceRequest {
+    fn clear(&mut self) {
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for TrialBalanceRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for TrialBalanceRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct TrialBalance {
+    // message fields
+    pub accounts: ::protobuf::RepeatedField<LedgerBalance>,
+    pub unbalanced: ::std::vec::Vec<i64>,
+    pub total_debit: i64,
+    pub total_credit: i64,
+    pub balanced: bool,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+impl TrialBalance {
+    pub fn new() -> TrialBalance {
+        ::std::default::Default::default()
+    }
+
+    // repeated .LedgerBalance accounts = 1;
+
+    pub fn clear_accounts(&mut self) {
+        self.accounts.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_accounts(&mut self, v: ::protobuf::RepeatedField<LedgerBalance>) {
+        self.accounts = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_accounts(&mut self) -> &mut ::protobuf::RepeatedField<LedgerBalance> {
+        &mut self.accounts
+    }
+
+    // Take field
+    pub fn take_accounts(&mut self) -> ::protobuf::RepeatedField<LedgerBalance> {
+        ::std::mem::replace(&mut self.accounts, ::protobuf::RepeatedField::new())
+    }
+
+    pub fn get_accounts(&self) -> &[LedgerBalance] {
+        &self.accounts
+    }
+
+    // repeated int64 unbalanced = 2;
+
+    pub fn clear_unbalanced(&mut self) {
+        self.unbalanced.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_unbalanced(&mut self, v: ::std::vec::Vec<i64>) {
+        self.unbalanced = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_unbalanced(&mut self) -> &mut ::std::vec::Vec<i64> {
+        &mut self.unbalanced
+    }
+
+    // Take field
+    pub fn take_unbalanced(&mut self) -> ::std::vec::Vec<i64> {
+        ::std::mem::replace(&mut self.unbalanced, ::std::vec::Vec::new())
+    }
+
+    pub fn get_unbalanced(&self) -> &[i64] {
+        &self.unbalanced
+    }
+
+    // int64 total_debit = 3;
+
+    pub fn clear_total_debit(&mut self) {
+        self.total_debit = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_total_debit(&mut self, v: i64) {
+        self.total_debit = v;
+    }
+
+    pub fn get_total_debit(&self) -> i64 {
+        self.total_debit
+    }
+
+    // int64 total_credit = 4;
+
+    pub fn clear_total_credit(&mut self) {
+        self.total_credit = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_total_credit(&mut self, v: i64) {
+        self.total_credit = v;
+    }
+
+    pub fn get_total_credit(&self) -> i64 {
+        self.total_credit
+    }
+
+    // bool balanced = 5;
+
+    pub fn clear_balanced(&mut self) {
+        self.balanced = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_balanced(&mut self, v: bool) {
+        self.balanced = v;
+    }
+
+    pub fn get_balanced(&self) -> bool {
+        self.balanced
+    }
+}
+
+impl ::protobuf::Message for TrialBalance {
+    fn is_initialized(&self) -> bool {
+        for v in &self.accounts {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.accounts)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_repeated_int64_into(wire_type, is, &mut self.unbalanced)?;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.total_debit = tmp;
+                },
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.total_credit = tmp;
+                },
+                5 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.balanced = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        for value in &self.accounts {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
+        for value in &self.unbalanced {
+            my_size += ::protobuf::rt::value_size(2, *value, ::protobuf::wire_format::WireTypeVarint);
+        };
+        if self.total_debit != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.total_debit, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.total_credit != 0 {
+            my_size += ::protobuf::rt::value_size(4, self.total_credit, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.balanced != false {
+            my_size += 2;
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        for v in &self.accounts {
+            os.write_tag(1, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
+        for v in &self.unbalanced {
+            os.write_int64(2, *v)?;
+        };
+        if self.total_debit != 0 {
+            os.write_int64(3, self.total_debit)?;
+        }
+        if self.total_credit != 0 {
+            os.write_int64(4, self.total_credit)?;
+        }
+        if self.balanced != false {
+            os.write_bool(5, self.balanced)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> TrialBalance {
+        TrialBalance::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<LedgerBalance>>(
+                    "accounts",
+                    |m: &TrialBalance| { &m.accounts },
+                    |m: &mut TrialBalance| { &mut m.accounts },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_vec_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "unbalanced",
+                    |m: &TrialBalance| { &m.unbalanced },
+                    |m: &mut TrialBalance| { &mut m.unbalanced },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "total_debit",
+                    |m: &TrialBalance| { &m.total_debit },
+                    |m: &mut TrialBalance| { &mut m.total_debit },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "total_credit",
+                    |m: &TrialBalance| { &m.total_credit },
+                    |m: &mut TrialBalance| { &mut m.total_credit },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                    "balanced",
+                    |m: &TrialBalance| { &m.balanced },
+                    |m: &mut TrialBalance| { &mut m.balanced },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<TrialBalance>(
+                    "TrialBalance",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static TrialBalance {
+        static mut instance: ::protobuf::lazy::Lazy<TrialBalance> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const TrialBalance,
+        };
+        unsafe {
+            instance.get(TrialBalance::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for TrialBalance {
+    fn clear(&mut self) {
+        self.clear_accounts();
+        self.clear_unbalanced();
+        self.clear_total_debit();
+        self.clear_total_credit();
+        self.clear_balanced();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for TrialBalance {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for TrialBalance {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct LedgerBalance {
+    // message fields
+    pub account: i64,
+    pub credit_type: ::std::string::String,
+    pub ledger_account: ::std::string::String,
+    pub debit: i64,
+    pub credit: i64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+impl LedgerBalance {
+    pub fn new() -> LedgerBalance {
+        ::std::default::Default::default()
+    }
+
+    // int64 account = 1;
+
+    pub fn clear_account(&mut self) {
+        self.account = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_account(&mut self, v: i64) {
+        self.account = v;
+    }
+
+    pub fn get_account(&self) -> i64 {
+        self.account
+    }
+
+    // string credit_type = 2;
+
+    pub fn clear_credit_type(&mut self) {
+        self.credit_type.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_credit_type(&mut self, v: ::std::string::String) {
+        self.credit_type = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_credit_type(&mut self) -> &mut ::std::string::String {
+        &mut self.credit_type
+    }
+
+    // Take field
+    pub fn take_credit_type(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.credit_type, ::std::string::String::new())
+    }
+
+    pub fn get_credit_type(&self) -> &str {
+        &self.credit_type
+    }
+
+    // string ledger_account = 3;
+
+    pub fn clear_ledger_account(&mut self) {
+        self.ledger_account.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_ledger_account(&mut self, v: ::std::string::String) {
+        self.ledger_account = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_ledger_account(&mut self) -> &mut ::std::string::String {
+        &mut self.ledger_account
+    }
+
+    // Take field
+    pub fn take_ledger_account(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.ledger_account, ::std::string::String::new())
+    }
+
+    pub fn get_ledger_account(&self) -> &str {
+        &self.ledger_account
+    }
+
+    // int64 debit = 4;
+
+    pub fn clear_debit(&mut self) {
+        self.debit = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_debit(&mut self, v: i64) {
+        self.debit = v;
+    }
+
+    pub fn get_debit(&self) -> i64 {
+        self.debit
+    }
+
+    // int64 credit = 5;
+
+    pub fn clear_credit(&mut self) {
+        self.credit = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_credit(&mut self, v: i64) {
+        self.credit = v;
+    }
+
+    pub fn get_credit(&self) -> i64 {
+        self.credit
+    }
+}
+
+impl ::protobuf::Message for LedgerBalance {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.account = tmp;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.credit_type)?;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.ledger_account)?;
+                },
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.debit = tmp;
+                },
+                5 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.credit = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.account != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.account, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.credit_type.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.credit_type);
+        }
+        if !self.ledger_account.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.ledger_account);
+        }
+        if self.debit != 0 {
+            my_size += ::protobuf::rt::value_size(4, self.debit, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.credit != 0 {
+            my_size += ::protobuf::rt::value_size(5, self.credit, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        if self.account != 0 {
+            os.write_int64(1, self.account)?;
+        }
+        if !self.credit_type.is_empty() {
+            os.write_string(2, &self.credit_type)?;
+        }
+        if !self.ledger_account.is_empty() {
+            os.write_string(3, &self.ledger_account)?;
+        }
+        if self.debit != 0 {
+            os.write_int64(4, self.debit)?;
+        }
+        if self.credit != 0 {
+            os.write_int64(5, self.credit)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> LedgerBalance {
+        LedgerBalance::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "account",
+                    |m: &LedgerBalance| { &m.account },
+                    |m: &mut LedgerBalance| { &mut m.account },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "credit_type",
+                    |m: &LedgerBalance| { &m.credit_type },
+                    |m: &mut LedgerBalance| { &mut m.credit_type },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "ledger_account",
+                    |m: &LedgerBalance| { &m.ledger_account },
+                    |m: &mut LedgerBalance| { &mut m.ledger_account },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "debit",
+                    |m: &LedgerBalance| { &m.debit },
+                    |m: &mut LedgerBalance| { &mut m.debit },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                    "credit",
+                    |m: &LedgerBalance| { &m.credit },
+                    |m: &mut LedgerBalance| { &mut m.credit },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<LedgerBalance>(
+                    "LedgerBalance",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static LedgerBalance {
+        static mut instance: ::protobuf::lazy::Lazy<LedgerBalance> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const LedgerBalance,
+        };
+        unsafe {
+            instance.get(LedgerBalance::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for LedgerBalance {
+    fn clear(&mut self) {
+        self.clear_account();
+        self.clear_credit_type();
+        self.clear_ledger_account();
+        self.clear_debit();
+        self.clear_credit();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for LedgerBalance {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for LedgerBalance {
     fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
         ::protobuf::reflect::ProtobufValueRef::Message(self)
     }
 }
 
 static file_descriptor_proto_data: &'static [u8] = b"\
-    \n\rcredits.proto\"E\n\x11AddCreditsCommand\x12\x18\n\x07account\x18\x01\
+    \n\rcredits.proto\"f\n\x11AddCreditsCommand\x12\x18\n\x07account\x18\x01\
     \x20\x01(\x03R\x07account\x12\x16\n\x06amount\x18\x02\x20\x01(\x03R\x06a\
-    mount\"3\n\x12AddCreditsResponse\x12\x1d\n\nnew_amount\x18\x01\x20\x01(\
-    \x03R\tnewAmount\"0\n\x14AccountStatusRequest\x12\x18\n\x07account\x18\
-    \x01\x20\x01(\x03R\x07account\"'\n\rAccountStatus\x12\x16\n\x06amount\
-    \x18\x01\x20\x01(\x03R\x06amount2\x7f\n\x07Credits\x12;\n\x10GetAccountS\
-    tatus\x12\x15.AccountStatusRequest\x1a\x0e.AccountStatus\"\0\x127\n\nAdd\
-    Credits\x12\x12.AddCreditsCommand\x1a\x13.AddCreditsResponse\"\0J\xd6\
-    \x04\n\x06\x12\x04\0\0\x16\x01\n\x08\n\x01\x0c\x12\x03\0\0\x12\n\n\n\x02\
-    \x06\0\x12\x04\x02\0\x05\x01\n\n\n\x03\x06\0\x01\x12\x03\x02\x08\x0f\n\
-    \x0b\n\x04\x06\0\x02\0\x12\x03\x03\x04I\n\x0c\n\x05\x06\0\x02\0\x01\x12\
-    \x03\x03\x08\x18\n\x0c\n\x05\x06\0\x02\0\x02\x12\x03\x03\x19-\n\x0c\n\
-    \x05\x06\0\x02\0\x03\x12\x03\x038E\n\x0b\n\x04\x06\0\x02\x01\x12\x03\x04\
-    \x04E\n\x0c\n\x05\x06\0\x02\x01\x01\x12\x03\x04\x08\x12\n\x0c\n\x05\x06\
-    \0\x02\x01\x02\x12\x03\x04\x13$\n\x0c\n\x05\x06\0\x02\x01\x03\x12\x03\
-    \x04/A\n\n\n\x02\x04\0\x12\x04\x07\0\n\x01\n\n\n\x03\x04\0\x01\x12\x03\
-    \x07\x08\x19\n\x0b\n\x04\x04\0\x02\0\x12\x03\x08\x04\x16\n\r\n\x05\x04\0\
-    \x02\0\x04\x12\x04\x08\x04\x07\x1b\n\x0c\n\x05\x04\0\x02\0\x05\x12\x03\
-    \x08\x04\t\n\x0c\n\x05\x04\0\x02\0\x01\x12\x03\x08\n\x11\n\x0c\n\x05\x04\
-    \0\x02\0\x03\x12\x03\x08\x14\x15\n\x0b\n\x04\x04\0\x02\x01\x12\x03\t\x04\
-    \x15\n\r\n\x05\x04\0\x02\x01\x04\x12\x04\t\x04\x08\x16\n\x0c\n\x05\x04\0\
-    \x02\x01\x05\x12\x03\t\x04\t\n\x0c\n\x05\x04\0\x02\x01\x01\x12\x03\t\n\
-    \x10\n\x0c\n\x05\x04\0\x02\x01\x03\x12\x03\t\x13\x14\n\n\n\x02\x04\x01\
-    \x12\x04\x0c\0\x0e\x01\n\n\n\x03\x04\x01\x01\x12\x03\x0c\x08\x1a\n\x0b\n\
-    \x04\x04\x01\x02\0\x12\x03\r\x04\x19\n\r\n\x05\x04\x01\x02\0\x04\x12\x04\
-    \r\x04\x0c\x1c\n\x0c\n\x05\x04\x01\x02\0\x05\x12\x03\r\x04\t\n\x0c\n\x05\
-    \x04\x01\x02\0\x01\x12\x03\r\n\x14\n\x0c\n\x05\x04\x01\x02\0\x03\x12\x03\
-    \r\x17\x18\n\n\n\x02\x04\x02\x12\x04\x10\0\x12\x01\n\n\n\x03\x04\x02\x01\
-    \x12\x03\x10\x08\x1c\n\x0b\n\x04\x04\x02\x02\0\x12\x03\x11\x04\x16\n\r\n\
-    \x05\x04\x02\x02\0\x04\x12\x04\x11\x04\x10\x1e\n\x0c\n\x05\x04\x02\x02\0\
-    \x05\x12\x03\x11\x04\t\n\x0c\n\x05\x04\x02\x02\0\x01\x12\x03\x11\n\x11\n\
-    \x0c\n\x05\x04\x02\x02\0\x03\x12\x03\x11\x14\x15\n\n\n\x02\x04\x03\x12\
-    \x04\x14\0\x16\x01\n\n\n\x03\x04\x03\x01\x12\x03\x14\x08\x15\n\x0b\n\x04\
-    \x04\x03\x02\0\x12\x03\x15\x04\x15\n\r\n\x05\x04\x03\x02\0\x04\x12\x04\
-    \x15\x04\x14\x17\n\x0c\n\x05\x04\x03\x02\0\x05\x12\x03\x15\x04\t\n\x0c\n\
-    \x05\x04\x03\x02\0\x01\x12\x03\x15\n\x10\n\x0c\n\x05\x04\x03\x02\0\x03\
-    \x12\x03\x15\x13\x14b\x06proto3\
+    mount\x12\x1f\n\x0bcredit_type\x18\x03\x20\x01(\tR\ncreditType\"3\n\x12A\
+    ddCreditsResponse\x12\x1d\n\nnew_amount\x18\x01\x20\x01(\x03R\tnewAmount\
+    \"\x91\x01\n\x16TransferCreditsCommand\x12\x16\n\x06source\x18\x01\x20\
+    \x01(\x03R\x06source\x12\x16\n\x06target\x18\x02\x20\x01(\x03R\x06target\
+    \x12\x16\n\x06amount\x18\x03\x20\x01(\x03R\x06amount\x12\x1f\n\x0bcredit\
+    _type\x18\x04\x20\x01(\tR\ncreditType\x12\x0e\n\x02id\x18\x05\x20\x01(\t\
+    R\x02id\"?\n\x17TransferCreditsResponse\x12\x0e\n\x02id\x18\x01\x20\x01(\
+    \tR\x02id\x12\x14\n\x05state\x18\x02\x20\x01(\tR\x05state\"z\n\x15Reserv\
+    eCreditsCommand\x12\x18\n\x07account\x18\x01\x20\x01(\x03R\x07account\
+    \x12\x0e\n\x02id\x18\x02\x20\x01(\tR\x02id\x12\x16\n\x06amount\x18\x03\
+    \x20\x01(\x03R\x06amount\x12\x1f\n\x0bcredit_type\x18\x04\x20\x01(\tR\nc\
+    reditType\"7\n\x16ReserveCreditsResponse\x12\x1d\n\nnew_amount\x18\x01\
+    \x20\x01(\x03R\tnewAmount\"M\n\x15AttachContractCommand\x12\x1a\n\x08cus\
+    tomer\x18\x01\x20\x01(\x03R\x08customer\x12\x18\n\x07account\x18\x02\x20\
+    \x01(\x03R\x07account\"\x18\n\x16AttachContractResponse\"3\n\x15Customer\
+    StatusRequest\x12\x1a\n\x08customer\x18\x01\x20\x01(\x03R\x08customer\"x\
+    \n\x0eCustomerStatus\x12\x1c\n\tavailable\x18\x01\x20\x01(\x03R\tavailab\
+    le\x12\x1a\n\x08reserved\x18\x02\x20\x01(\x03R\x08reserved\x12,\n\x08bal\
+    ances\x18\x03\x20\x03(\x0b2\x10.CustomerBalanceR\x08balances\"l\n\x0fCus\
+    tomerBalance\x12\x1f\n\x0bcredit_type\x18\x01\x20\x01(\tR\ncreditType\
+    \x12\x1c\n\tavailable\x18\x02\x20\x01(\x03R\tavailable\x12\x1a\n\x08rese\
+    rved\x18\x03\x20\x01(\x03R\x08reserved\"0\n\x14AccountStatusRequest\x12\
+    \x18\n\x07account\x18\x01\x20\x01(\x03R\x07account\"S\n\rAccountStatus\
+    \x12\x16\n\x06amount\x18\x01\x20\x01(\x03R\x06amount\x12*\n\x08balances\
+    \x18\x02\x20\x03(\x0b2\x0e.CreditBalanceR\x08balances\"^\n\rCreditBalanc\
+    e\x12\x1f\n\x0bcredit_type\x18\x01\x20\x01(\tR\ncreditType\x12\x16\n\x06\
+    amount\x18\x02\x20\x01(\x03R\x06amount\x12\x14\n\x05spent\x18\x03\x20\
+    \x01(\x03R\x05spent\"\x15\n\x13TrialBalanceRequest\"\xba\x01\n\x0cTrialB\
+    alance\x12*\n\x08accounts\x18\x01\x20\x03(\x0b2\x0e.LedgerBalanceR\x08ac\
+    counts\x12\x1e\n\nunbalanced\x18\x02\x20\x03(\x03R\nunbalanced\x12\x1f\n\
+    \x0btotal_debit\x18\x03\x20\x01(\x03R\ntotalDebit\x12!\n\x0ctotal_credit\
+    \x18\x04\x20\x01(\x03R\x0btotalCredit\x12\x1a\n\x08balanced\x18\x05\x20\
+    \x01(\x08R\x08balanced\"\x9f\x01\n\rLedgerBalance\x12\x18\n\x07account\
+    \x18\x01\x20\x01(\x03R\x07account\x12\x1f\n\x0bcredit_type\x18\x02\x20\
+    \x01(\tR\ncreditType\x12%\n\x0eledger_account\x18\x03\x20\x01(\tR\rledge\
+    rAccount\x12\x14\n\x05debit\x18\x04\x20\x01(\x03R\x05debit\x12\x16\n\x06\
+    credit\x18\x05\x20\x01(\x03R\x06credit2\xcb\x03\n\x07Credits\x12;\n\x10G\
+    etAccountStatus\x12\x15.AccountStatusRequest\x1a\x0e.AccountStatus\"\0\
+    \x127\n\nAddCredits\x12\x12.AddCreditsCommand\x1a\x13.AddCreditsResponse\
+    \"\0\x12F\n\x0fTransferCredits\x12\x17.TransferCreditsCommand\x1a\x18.Tr\
+    ansferCreditsResponse\"\0\x12C\n\x0eReserveCredits\x12\x16.ReserveCredit\
+    sCommand\x1a\x17.ReserveCreditsResponse\"\0\x12C\n\x0eAttachContract\x12\
+    \x16.AttachContractCommand\x1a\x17.AttachContractResponse\"\0\x12>\n\x11\
+    GetCustomerStatus\x12\x16.CustomerStatusRequest\x1a\x0f.CustomerStatus\"\
+    \0\x128\n\x0fGetTrialBalance\x12\x14.TrialBalanceRequest\x1a\r.TrialBala\
+    nce\"\0b\x06proto3\
 ";
 
 static mut file_descriptor_proto_lazy: ::protobuf::lazy::Lazy<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::lazy::Lazy {
@@ -32,6 +32,41 @@ const METHOD_CREDITS_ADD_CREDITS: ::grpcio::Method<super::credits::AddCreditsCom
     resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
 };
 
+const METHOD_CREDITS_TRANSFER_CREDITS: ::grpcio::Method<super::credits::TransferCreditsCommand, super::credits::TransferCreditsResponse> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/Credits/TransferCredits",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
+const METHOD_CREDITS_RESERVE_CREDITS: ::grpcio::Method<super::credits::ReserveCreditsCommand, super::credits::ReserveCreditsResponse> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/Credits/ReserveCredits",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
+const METHOD_CREDITS_ATTACH_CONTRACT: ::grpcio::Method<super::credits::AttachContractCommand, super::credits::AttachContractResponse> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/Credits/AttachContract",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
+const METHOD_CREDITS_GET_CUSTOMER_STATUS: ::grpcio::Method<super::credits::CustomerStatusRequest, super::credits::CustomerStatus> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/Credits/GetCustomerStatus",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
+const METHOD_CREDITS_GET_TRIAL_BALANCE: ::grpcio::Method<super::credits::TrialBalanceRequest, super::credits::TrialBalance> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/Credits/GetTrialBalance",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
 pub struct CreditsClient {
     client: ::grpcio::Client,
 }
@@ -74,6 +109,86 @@ impl CreditsClient {
     pub fn add_credits_async(&self, req: &super::credits::AddCreditsCommand) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::credits::AddCreditsResponse>> {
         self.add_credits_async_opt(req, ::grpcio::CallOption::default())
     }
+
+    pub fn transfer_credits_opt(&self, req: &super::credits::TransferCreditsCommand, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::credits::TransferCreditsResponse> {
+        self.client.unary_call(&METHOD_CREDITS_TRANSFER_CREDITS, req, opt)
+    }
+
+    pub fn transfer_credits(&self, req: &super::credits::TransferCreditsCommand) -> ::grpcio::Result<super::credits::TransferCreditsResponse> {
+        self.transfer_credits_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn transfer_credits_async_opt(&self, req: &super::credits::TransferCreditsCommand, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::credits::TransferCreditsResponse>> {
+        self.client.unary_call_async(&METHOD_CREDITS_TRANSFER_CREDITS, req, opt)
+    }
+
+    pub fn transfer_credits_async(&self, req: &super::credits::TransferCreditsCommand) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::credits::TransferCreditsResponse>> {
+        self.transfer_credits_async_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn reserve_credits_opt(&self, req: &super::credits::ReserveCreditsCommand, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::credits::ReserveCreditsResponse> {
+        self.client.unary_call(&METHOD_CREDITS_RESERVE_CREDITS, req, opt)
+    }
+
+    pub fn reserve_credits(&self, req: &super::credits::ReserveCreditsCommand) -> ::grpcio::Result<super::credits::ReserveCreditsResponse> {
+        self.reserve_credits_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn reserve_credits_async_opt(&self, req: &super::credits::ReserveCreditsCommand, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::credits::ReserveCreditsResponse>> {
+        self.client.unary_call_async(&METHOD_CREDITS_RESERVE_CREDITS, req, opt)
+    }
+
+    pub fn reserve_credits_async(&self, req: &super::credits::ReserveCreditsCommand) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::credits::ReserveCreditsResponse>> {
+        self.reserve_credits_async_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn attach_contract_opt(&self, req: &super::credits::AttachContractCommand, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::credits::AttachContractResponse> {
+        self.client.unary_call(&METHOD_CREDITS_ATTACH_CONTRACT, req, opt)
+    }
+
+    pub fn attach_contract(&self, req: &super::credits::AttachContractCommand) -> ::grpcio::Result<super::credits::AttachContractResponse> {
+        self.attach_contract_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn attach_contract_async_opt(&self, req: &super::credits::AttachContractCommand, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::credits::AttachContractResponse>> {
+        self.client.unary_call_async(&METHOD_CREDITS_ATTACH_CONTRACT, req, opt)
+    }
+
+    pub fn attach_contract_async(&self, req: &super::credits::AttachContractCommand) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::credits::AttachContractResponse>> {
+        self.attach_contract_async_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn get_customer_status_opt(&self, req: &super::credits::CustomerStatusRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::credits::CustomerStatus> {
+        self.client.unary_call(&METHOD_CREDITS_GET_CUSTOMER_STATUS, req, opt)
+    }
+
+    pub fn get_customer_status(&self, req: &super::credits::CustomerStatusRequest) -> ::grpcio::Result<super::credits::CustomerStatus> {
+        self.get_customer_status_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn get_customer_status_async_opt(&self, req: &super::credits::CustomerStatusRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::credits::CustomerStatus>> {
+        self.client.unary_call_async(&METHOD_CREDITS_GET_CUSTOMER_STATUS, req, opt)
+    }
+
+    pub fn get_customer_status_async(&self, req: &super::credits::CustomerStatusRequest) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::credits::CustomerStatus>> {
+        self.get_customer_status_async_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn get_trial_balance_opt(&self, req: &super::credits::TrialBalanceRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::credits::TrialBalance> {
+        self.client.unary_call(&METHOD_CREDITS_GET_TRIAL_BALANCE, req, opt)
+    }
+
+    pub fn get_trial_balance(&self, req: &super::credits::TrialBalanceRequest) -> ::grpcio::Result<super::credits::TrialBalance> {
+        self.get_trial_balance_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn get_trial_balance_async_opt(&self, req: &super::credits::TrialBalanceRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::credits::TrialBalance>> {
+        self.client.unary_call_async(&METHOD_CREDITS_GET_TRIAL_BALANCE, req, opt)
+    }
+
+    pub fn get_trial_balance_async(&self, req: &super::credits::TrialBalanceRequest) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::credits::TrialBalance>> {
+        self.get_trial_balance_async_opt(req, ::grpcio::CallOption::default())
+    }
     pub fn spawn<F>(&self, f: F) where F: ::futures::Future<Item = (), Error = ()> + Send + 'static {
         self.client.spawn(f)
     }
@@ -82,6 +197,11 @@ impl CreditsClient {
 pub trait Credits {
     fn get_account_status(&mut self, ctx: ::grpcio::RpcContext, req: super::credits::AccountStatusRequest, sink: ::grpcio::UnarySink<super::credits::AccountStatus>);
     fn add_credits(&mut self, ctx: ::grpcio::RpcContext, req: super::credits::AddCreditsCommand, sink: ::grpcio::UnarySink<super::credits::AddCreditsResponse>);
+    fn transfer_credits(&mut self, ctx: ::grpcio::RpcContext, req: super::credits::TransferCreditsCommand, sink: ::grpcio::UnarySink<super::credits::TransferCreditsResponse>);
+    fn reserve_credits(&mut self, ctx: ::grpcio::RpcContext, req: super::credits::ReserveCreditsCommand, sink: ::grpcio::UnarySink<super::credits::ReserveCreditsResponse>);
+    fn attach_contract(&mut self, ctx: ::grpcio::RpcContext, req: super::credits::AttachContractCommand, sink: ::grpcio::UnarySink<super::credits::AttachContractResponse>);
+    fn get_customer_status(&mut self, ctx: ::grpcio::RpcContext, req: super::credits::CustomerStatusRequest, sink: ::grpcio::UnarySink<super::credits::CustomerStatus>);
+    fn get_trial_balance(&mut self, ctx: ::grpcio::RpcContext, req: super::credits::TrialBalanceRequest, sink: ::grpcio::UnarySink<super::credits::TrialBalance>);
 }
 
 pub fn create_credits<S: Credits + Send + Clone + 'static>(s: S) -> ::grpcio::Service {
@@ -94,5 +214,25 @@ pub fn create_credits<S: Credits + Send + Clone + 'static>(s: S) -> ::grpcio::Se
     builder = builder.add_unary_handler(&METHOD_CREDITS_ADD_CREDITS, move |ctx, req, resp| {
         instance.add_credits(ctx, req, resp)
     });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_CREDITS_TRANSFER_CREDITS, move |ctx, req, resp| {
+        instance.transfer_credits(ctx, req, resp)
+    });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_CREDITS_RESERVE_CREDITS, move |ctx, req, resp| {
+        instance.reserve_credits(ctx, req, resp)
+    });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_CREDITS_ATTACH_CONTRACT, move |ctx, req, resp| {
+        instance.attach_contract(ctx, req, resp)
+    });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_CREDITS_GET_CUSTOMER_STATUS, move |ctx, req, resp| {
+        instance.get_customer_status(ctx, req, resp)
+    });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_CREDITS_GET_TRIAL_BALANCE, move |ctx, req, resp| {
+        instance.get_trial_balance(ctx, req, resp)
+    });
     builder.build()
 }
@@ -0,0 +1,166 @@
+use futures::{Future, Sink, Stream};
+use grpcio::{DuplexSink, RequestStream, RpcContext, RpcStatusCode, WriteFlags};
+use protobuf::{Message, RepeatedField};
+use protobuf::descriptor::{DescriptorProto, FileDescriptorProto};
+
+use {credits, health, reflection};
+use reflection::{ErrorResponse, ExtensionNumberResponse, FileDescriptorResponse, ListServiceResponse, ServiceResponse};
+use reflection::{ServerReflectionRequest, ServerReflectionResponse};
+use reflection::ServerReflectionRequest_oneof_message_request as Request;
+use reflection_grpc::ServerReflection;
+
+// gRPC server reflection, letting clients find out about the services served
+// and their messages without having the proto files
+#[derive(Clone, Default)]
+pub struct ReflectionSvc;
+
+// descriptors of the files defining the services served, none of which
+// imports another
+fn files() -> Vec<&'static FileDescriptorProto> {
+    vec![credits::file_descriptor_proto(), health::file_descriptor_proto(), reflection::file_descriptor_proto()]
+}
+
+fn qualified(file: &FileDescriptorProto, name: &str) -> String {
+    if file.get_package().is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", file.get_package(), name)
+    }
+}
+
+fn services() -> Vec<String> {
+    files().into_iter()
+        .flat_map(|f| f.get_service().iter().map(move |s| qualified(f, s.get_name())))
+        .collect()
+}
+
+// fully qualified names of the services, methods, messages and enums a file defines
+fn symbols(file: &FileDescriptorProto) -> Vec<String> {
+    let mut symbols = vec![];
+    for s in file.get_service() {
+        let name = qualified(file, s.get_name());
+        symbols.extend(s.get_method().iter().map(|m| format!("{}.{}", name, m.get_name())));
+        symbols.push(name);
+    }
+    for m in file.get_message_type() {
+        message_symbols(qualified(file, m.get_name()), m, &mut symbols);
+    }
+    symbols.extend(file.get_enum_type().iter().map(|e| qualified(file, e.get_name())));
+    symbols
+}
+
+fn message_symbols(name: String, message: &DescriptorProto, symbols: &mut Vec<String>) {
+    for m in message.get_nested_type() {
+        message_symbols(format!("{}.{}", name, m.get_name()), m, symbols);
+    }
+    symbols.extend(message.get_enum_type().iter().map(|e| format!("{}.{}", name, e.get_name())));
+    symbols.push(name);
+}
+
+fn is_message(file: &FileDescriptorProto, name: &str) -> bool {
+    file.get_message_type().iter().any(|m| qualified(file, m.get_name()) == name)
+}
+
+fn file_response(file: Option<&FileDescriptorProto>, what: &str) -> ServerReflectionResponse {
+    let mut resp = ServerReflectionResponse::new();
+    match file.map(|f| f.write_to_bytes()) {
+        Some(Ok(bytes)) => {
+            let mut files = FileDescriptorResponse::new();
+            files.set_file_descriptor_proto(RepeatedField::from_vec(vec![bytes]));
+            resp.set_file_descriptor_response(files);
+        },
+        Some(Err(e)) => resp.set_error_response(error(RpcStatusCode::Internal, &e.to_string())),
+        None => resp.set_error_response(error(RpcStatusCode::NotFound, &format!("{} not found", what)))
+    }
+    resp
+}
+
+fn error(code: RpcStatusCode, message: &str) -> ErrorResponse {
+    let mut e = ErrorResponse::new();
+    e.set_error_code(code as i32);
+    e.set_error_message(message.to_string());
+    e
+}
+
+fn respond(req: ServerReflectionRequest) -> ServerReflectionResponse {
+    let mut resp = match req.message_request.clone() {
+        Some(Request::list_services(_)) => {
+            let mut list = ListServiceResponse::new();
+            list.set_service(services().into_iter().map(|name| {
+                let mut s = ServiceResponse::new();
+                s.set_name(name);
+                s
+            }).collect());
+            let mut resp = ServerReflectionResponse::new();
+            resp.set_list_services_response(list);
+            resp
+        },
+        Some(Request::file_by_filename(name)) =>
+            file_response(files().into_iter().find(|f| f.get_name() == name), &name),
+        Some(Request::file_containing_symbol(symbol)) =>
+            file_response(files().into_iter().find(|f| symbols(f).contains(&symbol)), &symbol),
+        // proto3 has no extensions, so known messages have none
+        Some(Request::all_extension_numbers_of_type(name)) => {
+            let mut resp = ServerReflectionResponse::new();
+            if files().into_iter().any(|f| is_message(f, &name)) {
+                let mut numbers = ExtensionNumberResponse::new();
+                numbers.set_base_type_name(name);
+                resp.set_all_extension_numbers_response(numbers);
+            } else {
+                resp.set_error_response(error(RpcStatusCode::NotFound, &format!("{} not found", name)));
+            }
+            resp
+        },
+        Some(Request::file_containing_extension(ext)) =>
+            file_response(None, &format!("extension {} of {}", ext.get_extension_number(), ext.get_containing_type())),
+        None => {
+            let mut resp = ServerReflectionResponse::new();
+            resp.set_error_response(error(RpcStatusCode::InvalidArgument, "empty request"));
+            resp
+        }
+    };
+    resp.set_valid_host(req.get_host().to_string());
+    resp.set_original_request(req);
+    resp
+}
+
+impl ServerReflection for ReflectionSvc {
+    fn server_reflection_info(&mut self, ctx: RpcContext, stream: RequestStream<ServerReflectionRequest>, sink: DuplexSink<ServerReflectionResponse>) {
+        let responses = stream.map(|req| {
+            debug!(request = ?req, "reflection");
+            (respond(req), WriteFlags::default())
+        });
+        ctx.spawn(sink.send_all(responses)
+            .map(|_| ())
+            .map_err(|e| debug!(error = ?e, "reflection stream ended")));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(r: Request) -> ServerReflectionRequest {
+        let mut req = ServerReflectionRequest::new();
+        req.message_request = Some(r);
+        req
+    }
+
+    #[test]
+    fn it_lists_the_services() {
+        let resp = respond(request(Request::list_services(String::new())));
+        let names: Vec<&str> = resp.get_list_services_response().get_service().iter().map(|s| s.get_name()).collect();
+        assert_eq!(names, vec!["Credits", "grpc.health.v1.Health", "grpc.reflection.v1alpha.ServerReflection"]);
+    }
+
+    #[test]
+    fn it_finds_the_file_defining_a_symbol() {
+        let resp = respond(request(Request::file_containing_symbol("grpc.health.v1.HealthCheckResponse.ServingStatus".to_string())));
+        let bytes = &resp.get_file_descriptor_response().get_file_descriptor_proto()[0];
+        let file: FileDescriptorProto = ::protobuf::parse_from_bytes(bytes).unwrap();
+        assert_eq!(file.get_name(), "health.proto");
+
+        let resp = respond(request(Request::file_containing_symbol("Credits.Nothing".to_string())));
+        assert_eq!(resp.get_error_response().get_error_code(), RpcStatusCode::NotFound as i32);
+    }
+}
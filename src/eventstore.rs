@@ -1,38 +1,22 @@
-use r2d2_postgres::{PostgresConnectionManager, TlsMode};
-use r2d2::Pool;
-use {MyPool, CreditEvent, CreditError, Contract, Aggregate, CreditCommand};
+use r2d2_postgres::PostgresConnectionManager;
+use r2d2::{self, Pool};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use {MyPool, CreditError, Aggregate};
+use config::Config;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use serde_json;
 use postgres::error;
-use std::env;
-use dotenv;
-use Stopwatch;
+use postgres::GenericConnection;
+use postgres::transaction::Transaction;
+use tracing::field;
+use std::fmt::Debug;
+use metrics;
+use tls;
 
-pub fn init(pool: &MyPool) -> () {
-    let conn = pool.get().unwrap();
-    conn.batch_execute("
-        create table if not exists events (
-            id bigserial primary key,
-            aggregate_id bigint,
-            ts timestamp default current_timestamp,
-            payload text,
-            version bigint
-        );
-        
-        create index if not exists events_agg on events (aggregate_id);
-        create index if not exists events_agg_version on events (aggregate_id, version);
-        
-        create table if not exists aggregates (
-            id bigint primary key,
-            v bigint
-        );
-        
-        create table if not exists snapshots (
-            id bigint primary key,
-            v bigint not null,
-            ts timestamp default current_timestamp,
-            snapshot text not null
-        );").unwrap();
-}
+// events between snapshots of an aggregate, set from the config on startup
+static SNAPSHOT_INTERVAL: AtomicUsize = AtomicUsize::new(1000);
 
 impl From<error::Error> for CreditError {
     fn from(err: error::Error) -> Self {
@@ -40,148 +24,257 @@ impl From<error::Error> for CreditError {
     }
 }
 
+impl From<r2d2::Error> for CreditError {
+    fn from(err: r2d2::Error) -> Self {
+        CreditError::PoolError(err)
+    }
+}
+
 impl From<serde_json::Error> for CreditError {
     fn from(err: serde_json::Error) -> Self {
         CreditError::DataError(err)
     }
 }
 
-pub fn save_events(pool: &MyPool, aggregate: i64, expected_version: i64, version: i64, contract: &Contract, events: Vec<CreditEvent>) -> Result<(), CreditError> {
+pub fn save_events<A>(pool: &MyPool, expected_version: i64, aggregate: &A, events: Vec<A::Item>) -> Result<(), CreditError>
+    where A: Aggregate + Serialize, A::Item: Serialize
+{
     if events.len() == 0 {
         return Ok(())
     }
 
     let conn = pool.get().unwrap();
     let trx = conn.transaction()?;
+    save_events_in(&trx, expected_version, aggregate, events)?;
+
+    // commit all the things
+    trx.commit()?;
+
+    Ok(())
+}
+
+// same as save_events, but leaves committing to the caller so other writes
+// can be made atomically alongside the events
+pub fn save_events_in<A>(trx: &Transaction, expected_version: i64, aggregate: &A, events: Vec<A::Item>) -> Result<(), CreditError>
+    where A: Aggregate + Serialize, A::Item: Serialize
+{
+    let id = aggregate.id();
+    let version = aggregate.version();
+    let span = debug_span!("save_events", aggregate_type = A::TYPE, id, expected_version, version, events = events.len());
+    let _enter = span.enter();
 
     // conditionally update version from expected value to its current value.
     // if it fails it means somebody else managed to successfully complete an
     // operation on this aggregate before we could finish ourselves
     let affected = trx.execute(
-        "insert into aggregates (id, v) values ($2, $1)
-        on conflict(id) do update set v = $1 where aggregates.v = $3",
-        &[&version, &aggregate, &expected_version])?;
+        "insert into aggregates (aggregate_type, id, v) values ($4, $2, $1)
+        on conflict(aggregate_type, id) do update set v = $1 where aggregates.v = $3",
+        &[&version, &id, &expected_version, &A::TYPE])?;
 
     if affected == 1 {
         // find out if we need to snapshot first
-        let snapshots = trx.query("select id from snapshots where id = $1 and v >= $2 for update", &[&aggregate, &(version-1000)])?;
+        let snapshots = trx.query(
+            "select id from snapshots where aggregate_type = $1 and id = $2 and v >= $3 for update",
+            &[&A::TYPE, &id, &(version - SNAPSHOT_INTERVAL.load(Ordering::Relaxed) as i64)])?;
         
-        // prepare and write events to table
-        let stmt = trx.prepare("insert into events (aggregate_id, payload, version) values($1, $2, $3)")?;
+        // prepare and write events to table, each with the version the
+        // aggregate reached by applying it
+        let stmt = trx.prepare("insert into events (aggregate_type, aggregate_id, payload, version) values($1, $2, $3, $4)")?;
         
-        for evt in events.iter() {
+        for (i, evt) in events.iter().enumerate() {
             let ser = serde_json::to_string(evt).unwrap();
-            stmt.execute(&[&aggregate, &ser, &version])?;
+            stmt.execute(&[&A::TYPE, &id, &ser, &(expected_version + 1 + i as i64)])?;
         }
 
         // update snapshot
         if snapshots.is_empty() {
-            info!("writing snapshot of {} at {}", aggregate, version);
-            let snap = serde_json::to_string(contract)?;
-            trx.execute(
-                "insert into snapshots (id, v, snapshot) values ($1, $2, $3) on conflict(id) do update set v = $2, snapshot = $3",
-                &[&aggregate, &version, &snap])?;
+            save_snapshot(trx, aggregate)?;
         }
 
-        // commit all the things
-        trx.commit()?;
-
+        metrics::EVENTS_WRITTEN.with_label_values(&[A::TYPE]).inc_by(events.len() as i64);
         return Ok(())
     } else {
+        debug!("concurrency conflict");
+        metrics::CONCURRENCY_CONFLICTS.with_label_values(&[A::TYPE]).inc();
         return Err(CreditError::ConcurrencyError)
     }
 }
 
-pub fn load_snapshot(id: i64, pool: &MyPool) -> Result<Option<Contract>, CreditError> {
+fn save_snapshot<A>(conn: &dyn GenericConnection, aggregate: &A) -> Result<(), CreditError>
+    where A: Aggregate + Serialize
+{
+    info!(aggregate_type = A::TYPE, id = aggregate.id(), version = aggregate.version(), "writing snapshot");
+    let snap = serde_json::to_string(aggregate)?;
+    conn.execute(
+        "insert into snapshots (aggregate_type, id, v, snapshot) values ($1, $2, $3, $4)
+        on conflict(aggregate_type, id) do update set v = $3, snapshot = $4",
+        &[&A::TYPE, &aggregate.id(), &aggregate.version(), &snap])?;
+    metrics::SNAPSHOTS_WRITTEN.with_label_values(&[A::TYPE]).inc();
+    Ok(())
+}
+
+// Replaces the snapshot of an aggregate with one built from its events alone,
+// returning the version it was taken at
+pub fn rebuild_snapshot<A>(id: i64, pool: &MyPool) -> Result<i64, CreditError>
+    where A: Aggregate + Serialize, A::Item: DeserializeOwned
+{
+    let mut a = A::new(id);
+    load_into(&mut a, pool)?;
     let conn = pool.get().unwrap();
-    let snaps = conn.query("select snapshot from snapshots where id = $1", &[&id])?;
+    save_snapshot(&*conn, &a)?;
+    Ok(a.version())
+}
+
+pub fn load_snapshot<A>(id: i64, pool: &MyPool) -> Result<Option<A>, CreditError>
+    where A: Aggregate + DeserializeOwned
+{
+    let conn = pool.get().unwrap();
+    let snaps = conn.query("select snapshot from snapshots where aggregate_type = $1 and id = $2", &[&A::TYPE, &id])?;
     if !snaps.is_empty() {
         let row = snaps.get(0);
         let snap: String = row.get(0);
-        let deser: Result<Contract, _> = serde_json::from_str(&snap);
+        let deser: Result<A, _> = serde_json::from_str(&snap);
         if let Ok(c) = deser {
-            info!("loaded snapshot of agg {}", id);
+            debug!(version = c.version(), "loaded snapshot");
             return Ok(Some(c));
         }
     }
     Ok(None)
 }
 
-pub fn load(id: i64, pool: &MyPool) -> Result<Contract, CreditError> {
+// raw snapshot of an aggregate, whether or not it still deserializes
+pub fn load_snapshot_value<A: Aggregate>(id: i64, pool: &MyPool) -> Result<Option<serde_json::Value>, CreditError> {
+    let conn = pool.get().unwrap();
+    let snaps = conn.query("select snapshot from snapshots where aggregate_type = $1 and id = $2", &[&A::TYPE, &id])?;
+    if snaps.is_empty() {
+        return Ok(None)
+    }
+    let snap: String = snaps.get(0).get(0);
+    Ok(Some(serde_json::from_str(&snap)?))
+}
+
+// every aggregate of a type along with its recorded version
+pub fn aggregate_versions<A: Aggregate>(pool: &MyPool) -> Result<Vec<(i64, i64)>, CreditError> {
+    let conn = pool.get().unwrap();
+    let rows = conn.query("select id, v from aggregates where aggregate_type = $1 order by id", &[&A::TYPE])?;
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+}
+
+pub fn load<A>(id: i64, pool: &MyPool) -> Result<A, CreditError>
+    where A: Aggregate + DeserializeOwned, A::Item: DeserializeOwned
+{
+    let span = debug_span!("load", aggregate_type = A::TYPE, id, version = field::Empty);
+    let _enter = span.enter();
+    let _timer = metrics::LOAD_DURATION.with_label_values(&[A::TYPE]).start_timer();
     let mut c = match load_snapshot(id, pool)? {
         Some(c) => c,
-        None => {
-            let mut c = Contract::default();
-            c.id = id;
-            c
-        }
+        None => A::new(id)
     };
     
     load_into(&mut c, pool)?;
+    span.record("version", &c.version());
     Ok(c)
 }
 
-pub fn load_into(c: &mut Contract, pool: &MyPool) -> Result<(), CreditError> {
-    let evts = get_events(c.id(), c.version(), &pool)?;
+pub fn load_into<A>(c: &mut A, pool: &MyPool) -> Result<(), CreditError>
+    where A: Aggregate, A::Item: DeserializeOwned
+{
+    let evts = get_events::<A>(c.id(), c.version(), &pool)?;
+    metrics::EVENTS_REPLAYED.with_label_values(&[A::TYPE]).inc_by(evts.len() as i64);
     for evt in evts {
         c.apply(&evt);
     }
     Ok(())
 }
 
-pub fn get_events(id: i64, version: i64, pool: &MyPool) -> Result<Vec<CreditEvent>, CreditError> {
-    info!("loading events for agg {} from v{}", id, version);
+pub fn get_events<A>(id: i64, version: i64, pool: &MyPool) -> Result<Vec<A::Item>, CreditError>
+    where A: Aggregate, A::Item: DeserializeOwned
+{
+    debug!(aggregate_type = A::TYPE, id, from_version = version, "loading events");
     let conn = pool.get().unwrap();
     let rows = &conn.query(
-        "select payload from events where aggregate_id = $1 and version > $2 order by id asc",
-        &[&id, &version])?;
-    let events: Vec<CreditEvent> = rows.iter().map(|row| {
+        "select payload from events where aggregate_type = $1 and aggregate_id = $2 and version > $3 order by version asc",
+        &[&A::TYPE, &id, &version])?;
+    let events: Vec<A::Item> = rows.iter().map(|row| {
         let evt: String = row.get(0);
-        let credit_evt: CreditEvent = serde_json::from_str(&evt).unwrap();
+        let credit_evt: A::Item = serde_json::from_str(&evt).unwrap();
         credit_evt
     }).collect();
-    info!("loaded {} events", events.len());
+    debug!(events = events.len(), "loaded events");
 
     Ok(events)
 }
 
-pub fn pool() -> MyPool {
-    dotenv::dotenv().ok();
-    let url = env::var("DATABASE_URL").expect("Must have DATABASE_URL env var or in .env file");
-    let manager = PostgresConnectionManager::new(url, TlsMode::None).unwrap();
-    let pool = Pool::new(manager).unwrap();
+// seconds in the config, with 0 meaning never
+fn timeout(secs: u64) -> Option<Duration> {
+    if secs == 0 { None } else { Some(Duration::from_secs(secs)) }
+}
+
+pub fn pool(config: &Config) -> MyPool {
+    SNAPSHOT_INTERVAL.store(config.snapshot_interval as usize, Ordering::Relaxed);
+
+    let url = config.database_url.clone().expect("Must have DATABASE_URL env var, database_url setting or .env file");
+    let tls_mode = tls::database_tls_mode(config).unwrap_or_else(|e| panic!("{}", e));
+    let manager = PostgresConnectionManager::new(url, tls_mode).unwrap();
+    let pool = Pool::builder()
+        .max_size(config.pool_max_size)
+        .min_idle(config.pool_min_idle)
+        .connection_timeout(Duration::from_secs(config.pool_connection_timeout))
+        .idle_timeout(timeout(config.pool_idle_timeout))
+        .max_lifetime(timeout(config.pool_max_lifetime))
+        .build(manager)
+        .unwrap();
+    pool
+}
+
+// Pool for the tests that need a database, which are ignored unless asked
+// for, e.g. DATABASE_URL=postgres://localhost/credits_test cargo test -- --ignored
+#[cfg(test)]
+pub fn test_pool() -> MyPool {
+    let url = ::std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = pool(&Config { database_url: Some(url), ..Config::default() });
+    ::migrations::migrate(&pool).unwrap();
     pool
 }
 
-pub fn run_cmd(c: &mut Contract, cmd: CreditCommand) -> Result<Vec<CreditEvent>, CreditError> {
+pub fn run_cmd<A>(c: &mut A, cmd: A::Cmd) -> Result<Vec<A::Item>, A::Error>
+    where A: Aggregate, A::Cmd: Debug, A::Error: Debug
+{
+    let span = debug_span!("handle", aggregate_type = A::TYPE, id = c.id(), version = c.version(), command = ?cmd);
+    let _enter = span.enter();
     let res = c.handle(&cmd);
     match res {
         Ok(evts) => {
+            debug!(events = evts.len(), "handled");
             for e in evts.iter() {
                 c.apply(e);
+                trace!(version = c.version(), "applied");
             }
             Ok(evts)
         },
-        Err(e) => Err(e)
+        Err(e) => {
+            debug!(error = ?e, "rejected");
+            Err(e)
+        }
     }
 }
 
 #[allow(unused)]
-pub fn run_and_store(c: &mut Contract, cmd: CreditCommand, pool: &MyPool) -> Result<(), CreditError> {
+pub fn run_and_store<A>(c: &mut A, cmd: A::Cmd, pool: &MyPool) -> Result<(), A::Error>
+    where A: Aggregate + Serialize, A::Item: Serialize, A::Cmd: Debug, A::Error: From<CreditError> + Debug
+{
     run_and_store_batch(c, vec![cmd], pool)
 }
 
-pub fn run_and_store_batch(c: &mut Contract, cmds: Vec<CreditCommand>, pool: &MyPool) -> Result<(), CreditError> {
+pub fn run_and_store_batch<A>(c: &mut A, cmds: Vec<A::Cmd>, pool: &MyPool) -> Result<(), A::Error>
+    where A: Aggregate + Serialize, A::Item: Serialize, A::Cmd: Debug, A::Error: From<CreditError> + Debug
+{
     let expected_version = c.version();
     let mut all_evts = vec![];
     for cmd in cmds.into_iter() {
         let evts = run_cmd(c, cmd)?;
         all_evts.extend(evts);
     }
-    let current_version = c.version();
-    let sw = Stopwatch::start_new();
-    let event_count = all_evts.len();
-    save_events(pool, c.id(), expected_version, current_version, c, all_evts)?;
-    debug!("stored {} events in {}", event_count, sw.elapsed_ms());
+    save_events(pool, expected_version, c, all_evts)?;
     Ok(())
 }
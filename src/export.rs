@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use chrono::NaiveDateTime;
+use serde::de::DeserializeOwned;
+use serde_json::{self, Value};
+
+use audit::{replay, Audited};
+use person::Person;
+use {Aggregate, Contract, CreditError, MyPool};
+
+// how many rows are fetched from the events table at a time while exporting
+const BATCH_SIZE: i64 = 1000;
+
+// One event as written to or read from an export, one per line
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedEvent {
+    pub aggregate_type: String,
+    pub aggregate_id: i64,
+    pub version: i64,
+    pub ts: NaiveDateTime,
+    pub payload: Value
+}
+
+// Streams events of the aggregates with ids within the given range to `out`,
+// in the order they were stored. Returns how many were exported.
+pub fn export<F>(from: Option<i64>, to: Option<i64>, pool: &MyPool, mut out: F) -> Result<usize, CreditError>
+    where F: FnMut(ExportedEvent) -> Result<(), CreditError>
+{
+    let conn = pool.get().unwrap();
+    let stmt = conn.prepare(
+        "select id, aggregate_type, aggregate_id, version, ts, payload from events
+        where aggregate_id between $1 and $2 and id > $3 order by id asc limit $4")?;
+
+    let from = from.unwrap_or(i64::min_value());
+    let to = to.unwrap_or(i64::max_value());
+    let (mut count, mut last) = (0, 0i64);
+    loop {
+        let rows = stmt.query(&[&from, &to, &last, &BATCH_SIZE])?;
+        if rows.is_empty() {
+            break
+        }
+        for row in rows.iter() {
+            let payload: String = row.get(5);
+            last = row.get(0);
+            out(ExportedEvent {
+                aggregate_type: row.get(1),
+                aggregate_id: row.get(2),
+                version: row.get(3),
+                ts: row.get(4),
+                payload: serde_json::from_str(&payload)?
+            })?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+// Checks that the events of an aggregate are numbered one after another and
+// replay cleanly up to the version of the last one
+fn validate<A>(id: i64, events: &[&ExportedEvent]) -> Result<(), CreditError>
+    where A: Audited + ::serde::Serialize + DeserializeOwned, A::Item: DeserializeOwned
+{
+    let mut history = vec![];
+    let mut version = 0;
+    for e in events {
+        if e.version != version + 1 {
+            return Err(invalid(A::TYPE, id, format!("version {} follows version {}", e.version, version)))
+        }
+        version = e.version;
+        history.push(serde_json::from_value::<A::Item>(e.payload.clone())?);
+    }
+
+    let problems = replay::<A>(id, version, &history, None);
+    if !problems.is_empty() {
+        return Err(invalid(A::TYPE, id, problems.join("; ")))
+    }
+    Ok(())
+}
+
+fn invalid(aggregate_type: &str, id: i64, problem: String) -> CreditError {
+    CreditError::InvalidImport(format!("{} {}: {}", aggregate_type, id, problem))
+}
+
+// Loads exported events into an empty store, keeping their versions and
+// timestamps. Every aggregate is replayed first and nothing is written unless
+// all of them are valid.
+pub fn import(events: &[ExportedEvent], pool: &MyPool) -> Result<usize, CreditError> {
+    let mut aggregates: Vec<(&str, i64)> = vec![];
+    let mut by_aggregate: HashMap<(&str, i64), Vec<&ExportedEvent>> = HashMap::new();
+    for e in events {
+        let key = (e.aggregate_type.as_str(), e.aggregate_id);
+        by_aggregate.entry(key).or_insert_with(|| {
+            aggregates.push(key);
+            vec![]
+        }).push(e);
+    }
+
+    for key in aggregates.iter() {
+        let history = &by_aggregate[key];
+        match key.0 {
+            t if t == Contract::TYPE => validate::<Contract>(key.1, history)?,
+            t if t == Person::TYPE => validate::<Person>(key.1, history)?,
+            t => return Err(invalid(t, key.1, "unknown aggregate type".to_string()))
+        }
+    }
+
+    let conn = pool.get().unwrap();
+    let trx = conn.transaction()?;
+    if !trx.query("select 1 from events limit 1", &[])?.is_empty() {
+        return Err(CreditError::InvalidImport("store already holds events".to_string()))
+    }
+
+    let stmt = trx.prepare(
+        "insert into events (aggregate_type, aggregate_id, version, ts, payload) values ($1, $2, $3, $4, $5)")?;
+    for e in events {
+        let payload = serde_json::to_string(&e.payload)?;
+        stmt.execute(&[&e.aggregate_type, &e.aggregate_id, &e.version, &e.ts, &payload])?;
+    }
+
+    let stmt = trx.prepare("insert into aggregates (aggregate_type, id, v) values ($1, $2, $3)")?;
+    for key in aggregates.iter() {
+        let version = by_aggregate[key].last().map(|e| e.version).unwrap_or(0);
+        stmt.execute(&[&key.0, &key.1, &version])?;
+    }
+
+    trx.commit()?;
+    info!(events = events.len(), aggregates = aggregates.len(), "imported");
+    Ok(events.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+    use {CreditEvent, default_credit_type};
+
+    fn exported(version: i64, evt: CreditEvent) -> ExportedEvent {
+        ExportedEvent {
+            aggregate_type: Contract::TYPE.to_string(),
+            aggregate_id: 1,
+            version,
+            ts: Utc::now().naive_utc(),
+            payload: serde_json::to_value(evt).unwrap()
+        }
+    }
+
+    #[test]
+    fn it_accepts_consistent_history() {
+        let events = vec![
+            exported(1, CreditEvent::CreditsAdded { credit_type: default_credit_type(), amount: 10 }),
+            exported(2, CreditEvent::CreditLimitSet { credit_type: default_credit_type(), limit: 5 })
+        ];
+        validate::<Contract>(1, &events.iter().collect::<Vec<_>>()).unwrap();
+    }
+
+    #[test]
+    fn it_rejects_events_that_do_not_replay() {
+        let events = vec![
+            exported(1, CreditEvent::CreditsAdded { credit_type: default_credit_type(), amount: 10 }),
+            exported(2, CreditEvent::ReservationSpent { id: Uuid::new_v4(), amount: 5 })
+        ];
+        validate::<Contract>(1, &events.iter().collect::<Vec<_>>())
+            .expect_err("should not accept spending an unknown reservation");
+    }
+
+    #[test]
+    fn it_rejects_gaps_in_versions() {
+        let events = vec![
+            exported(1, CreditEvent::CreditsAdded { credit_type: default_credit_type(), amount: 10 }),
+            exported(3, CreditEvent::CreditLimitSet { credit_type: default_credit_type(), limit: 5 })
+        ];
+        validate::<Contract>(1, &events.iter().collect::<Vec<_>>())
+            .expect_err("should not accept a missing version");
+    }
+}
@@ -0,0 +1,186 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use futures::{Future, Sink, Stream};
+use futures::sync::mpsc;
+use grpcio::{self, RpcContext, RpcStatus, RpcStatusCode, ServerStreamingSink, UnarySink, WriteFlags};
+
+use health::{HealthCheckRequest, HealthCheckResponse};
+use health::HealthCheckResponse_ServingStatus as ServingStatus;
+use health_grpc::Health;
+use shutdown::{Shutdown, POLL_INTERVAL};
+use MyPool;
+
+// services health can be asked for, the empty name meaning the server as a whole
+const SERVICES: &[&str] = &["", "Credits"];
+
+// how often the status is checked for changes while anyone is watching
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+type Responses = mpsc::Sender<(HealthCheckResponse, WriteFlags)>;
+
+// a Watch call, along with the status it was last sent
+struct Watcher {
+    service: String,
+    last: ServingStatus,
+    tx: Responses
+}
+
+// The standard gRPC health service. The server is serving as long as the
+// database answers and it hasn't been asked to shut down.
+#[derive(Clone)]
+pub struct HealthSvc {
+    pool: MyPool,
+    shutdown: Shutdown,
+    watchers: Arc<Mutex<Vec<Watcher>>>
+}
+
+impl HealthSvc {
+    // Also starts the thread that checks the status for every Watch call at
+    // once, which stops on shutdown.
+    pub fn new(pool: MyPool, shutdown: Shutdown) -> Self {
+        let svc = HealthSvc { pool, shutdown, watchers: Arc::new(Mutex::new(vec![])) };
+        let poller = svc.clone();
+        thread::spawn(move || poller.poll());
+        svc
+    }
+
+    fn status(&self, service: &str) -> ServingStatus {
+        status_of(service, SERVICES.contains(&service) && self.is_serving())
+    }
+
+    fn is_serving(&self) -> bool {
+        !self.shutdown.is_requested() && self.database_is_up()
+    }
+
+    fn database_is_up(&self) -> bool {
+        let result = self.pool.get()
+            .map_err(|e| e.to_string())
+            .and_then(|conn| conn.execute("select 1", &[]).map_err(|e| e.to_string()));
+        if let Err(ref e) = result {
+            warn!(error = %e, "health check could not reach the database");
+        }
+        result.is_ok()
+    }
+
+    // Sends watchers the status whenever it changes, asking the database only
+    // while there are any. Once shutdown is requested they are told the server
+    // is not serving and their streams are ended.
+    fn poll(&self) {
+        loop {
+            let stopping = self.shutdown.is_requested();
+            let watched = !self.watchers.lock().unwrap().is_empty();
+            if watched || stopping {
+                // asked outside the lock so new watchers aren't held up
+                let serving = !stopping && self.database_is_up();
+                let mut watchers = self.watchers.lock().unwrap();
+                notify(&mut watchers, serving);
+                if stopping {
+                    watchers.clear();
+                    return
+                }
+            }
+            let next = Instant::now() + WATCH_INTERVAL;
+            while Instant::now() < next && !self.shutdown.is_requested() {
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+fn status_of(service: &str, serving: bool) -> ServingStatus {
+    if !SERVICES.contains(&service) {
+        ServingStatus::SERVICE_UNKNOWN
+    } else if serving {
+        ServingStatus::SERVING
+    } else {
+        ServingStatus::NOT_SERVING
+    }
+}
+
+// Sends each watcher its status if it changed, dropping those that went away.
+// A watcher not keeping up is sent the status on a later round.
+fn notify(watchers: &mut Vec<Watcher>, serving: bool) {
+    watchers.retain(|w| !w.tx.is_closed());
+    for w in watchers.iter_mut() {
+        let status = status_of(&w.service, serving);
+        if status != w.last && w.tx.try_send((response(status), WriteFlags::default())).is_ok() {
+            w.last = status;
+        }
+    }
+}
+
+fn response(status: ServingStatus) -> HealthCheckResponse {
+    let mut resp = HealthCheckResponse::new();
+    resp.set_status(status);
+    resp
+}
+
+impl Health for HealthSvc {
+    fn check(&mut self, ctx: RpcContext, req: HealthCheckRequest, sink: UnarySink<HealthCheckResponse>) {
+        let f = match self.status(req.get_service()) {
+            ServingStatus::SERVICE_UNKNOWN =>
+                sink.fail(RpcStatus::new(RpcStatusCode::NotFound, Some(format!("unknown service {}", req.get_service())))),
+            status => sink.success(response(status))
+        };
+        ctx.spawn(f.map_err(|e| error!(error = ?e, "could not send health")));
+    }
+
+    // Sends the status, and again whenever it changes. The stream ends after
+    // reporting the server not serving on shutdown, so it doesn't hold up
+    // draining the calls in flight.
+    fn watch(&mut self, ctx: RpcContext, req: HealthCheckRequest, sink: ServerStreamingSink<HealthCheckResponse>) {
+        let (mut tx, rx) = mpsc::channel(1);
+        let status = self.status(req.get_service());
+        // checked under the lock, so a watcher can't slip in after the
+        // poller let the others go on shutdown
+        let mut watchers = self.watchers.lock().unwrap();
+        if tx.try_send((response(status), WriteFlags::default())).is_ok() && !self.shutdown.is_requested() {
+            watchers.push(Watcher { service: req.get_service().to_string(), last: status, tx });
+        }
+        drop(watchers);
+
+        let responses = rx.map_err(|()| grpcio::Error::RemoteStopped);
+        ctx.spawn(sink.send_all(responses)
+            .map(|_| ())
+            .map_err(|e| debug!(error = ?e, "health watch ended")));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn watcher(service: &str, last: ServingStatus) -> (Watcher, mpsc::Receiver<(HealthCheckResponse, WriteFlags)>) {
+        let (tx, rx) = mpsc::channel(1);
+        (Watcher { service: service.to_string(), last, tx }, rx)
+    }
+
+    fn received(rx: &mut mpsc::Receiver<(HealthCheckResponse, WriteFlags)>) -> Option<ServingStatus> {
+        match rx.poll() {
+            Ok(::futures::Async::Ready(Some((resp, _)))) => Some(resp.get_status()),
+            _ => None
+        }
+    }
+
+    #[test]
+    fn it_sends_watchers_the_status_only_when_it_changes() {
+        let (server, mut server_rx) = watcher("", ServingStatus::SERVING);
+        let (credits, mut credits_rx) = watcher("Credits", ServingStatus::NOT_SERVING);
+        let (gone, gone_rx) = watcher("Credits", ServingStatus::SERVING);
+        drop(gone_rx);
+        let mut watchers = vec![server, credits, gone];
+
+        ::futures::future::lazy(|| {
+            notify(&mut watchers, true);
+            assert_eq!(watchers.len(), 2);
+            assert_eq!(received(&mut server_rx), None);
+            assert_eq!(received(&mut credits_rx), Some(ServingStatus::SERVING));
+
+            notify(&mut watchers, false);
+            assert_eq!(received(&mut server_rx), Some(ServingStatus::NOT_SERVING));
+            assert_eq!(received(&mut credits_rx), Some(ServingStatus::NOT_SERVING));
+            Ok::<(), ()>(())
+        }).wait().unwrap();
+    }
+}
@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+use chrono::NaiveDateTime;
+use postgres::transaction::Transaction;
+use uuid::Uuid;
+use serde_json;
+
+use {Aggregate, Amount, Contract, CreditError, CreditEvent, CreditType, MyPool};
+use CreditEvent::*;
+
+// name of this projection's checkpoint in the projections table
+const PROJECTION: &str = "ledger";
+
+// How far a projection has read the event log. Events are read in the order
+// of the transactions that wrote them, and by id within a transaction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Checkpoint {
+    pub txid: i64,
+    pub id: i64
+}
+
+// A contract event read by a projection
+pub struct ProjectedEvent {
+    pub id: i64,
+    pub txid: i64,
+    pub aggregate_id: i64,
+    pub ts: NaiveDateTime,
+    pub payload: String
+}
+
+impl ProjectedEvent {
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint { txid: self.txid, id: self.id }
+    }
+}
+
+// Reads the contract events after a projection's checkpoint, locking the
+// checkpoint until the transaction ends. Only events of transactions older
+// than every one still running are taken, so none can later be committed
+// behind the checkpoint, however long its writer took.
+pub fn events_after_checkpoint(trx: &Transaction, projection: &str) -> Result<(Checkpoint, Vec<ProjectedEvent>), CreditError> {
+    // before locking the checkpoint, which makes this transaction one of
+    // those still running
+    let rows = trx.query("select txid_snapshot_xmin(txid_current_snapshot())", &[])?;
+    let horizon: i64 = rows.get(0).get(0);
+
+    let rows = trx.query("select txid, position from projections where name = $1 for update", &[&projection])?;
+    let checkpoint = match rows.iter().next() {
+        Some(row) => Checkpoint { txid: row.get(0), id: row.get(1) },
+        None => Checkpoint { txid: 0, id: 0 }
+    };
+
+    let rows = trx.query(
+        "select id, txid, aggregate_id, ts, payload from events
+        where aggregate_type = $1 and (txid, id) > ($2, $3) and txid < $4
+        order by txid, id",
+        &[&Contract::TYPE, &checkpoint.txid, &checkpoint.id, &horizon])?;
+    let events = rows.iter()
+        .map(|row| ProjectedEvent { id: row.get(0), txid: row.get(1), aggregate_id: row.get(2), ts: row.get(3), payload: row.get(4) })
+        .collect();
+    Ok((checkpoint, events))
+}
+
+// Moves a projection's checkpoint to the last event it has taken in
+pub fn save_checkpoint(trx: &Transaction, projection: &str, checkpoint: Checkpoint) -> Result<(), CreditError> {
+    trx.execute(
+        "insert into projections (name, txid, position) values ($1, $2, $3)
+        on conflict(name) do update set txid = $2, position = $3",
+        &[&projection, &checkpoint.txid, &checkpoint.id])?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LedgerAccount {
+    // Contra account credits are issued from and removed to
+    Issued,
+    Available,
+    Reserved,
+    Allocated,
+    Spent,
+    // Reservations pass through here on their way back to available
+    Expired
+}
+
+impl LedgerAccount {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LedgerAccount::Issued => "issued",
+            LedgerAccount::Available => "available",
+            LedgerAccount::Reserved => "reserved",
+            LedgerAccount::Allocated => "allocated",
+            LedgerAccount::Spent => "spent",
+            LedgerAccount::Expired => "expired"
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Posting {
+    pub account: LedgerAccount,
+    pub debit: Amount,
+    pub credit: Amount
+}
+
+#[derive(Debug, Serialize)]
+pub struct LedgerBalance {
+    pub aggregate_id: i64,
+    pub credit_type: CreditType,
+    pub account: String,
+    pub debit: Amount,
+    pub credit: Amount
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct TrialBalance {
+    pub accounts: Vec<LedgerBalance>,
+    // contracts whose postings don't add up, should always be empty
+    pub unbalanced: Vec<i64>,
+    pub total_debit: Amount,
+    pub total_credit: Amount
+}
+
+impl TrialBalance {
+    pub fn is_balanced(&self) -> bool {
+        self.unbalanced.is_empty() && self.total_debit == self.total_credit
+    }
+}
+
+// moves an amount from one ledger account to another
+fn transfer(from: LedgerAccount, to: LedgerAccount, amount: Amount) -> Vec<Posting> {
+    vec![
+        Posting { account: to, debit: amount, credit: 0 },
+        Posting { account: from, debit: 0, credit: amount }
+    ]
+}
+
+// The reservation an event refers to, if any. Used to find the credit type
+// of events that only carry a reservation id.
+fn reference(evt: &CreditEvent) -> Option<Uuid> {
+    match evt {
+        &CreditsReserved { id, .. } |
+        &CreditsAllocated { id, .. } |
+        &ReservationCancelled(id, _) |
+        &ReservationExpired { id, .. } |
+        &AllocationFreed { id, .. } |
+        &ReservationSpent { id, .. } |
+        &SpendingRefunded { id, .. } => Some(id),
+        _ => None
+    }
+}
+
+// Balanced postings reflecting the effect of an event, empty for events that
+// don't move credits
+pub fn postings(evt: &CreditEvent) -> Vec<Posting> {
+    use self::LedgerAccount::*;
+
+    match evt {
+        &CreditsAdded { amount, .. } => transfer(Issued, Available, amount),
+        &CreditsRemoved { amount, .. } => transfer(Available, Issued, amount),
+        &CreditsReserved { amount, .. } => transfer(Available, Reserved, amount),
+        &CreditsAllocated { amount, .. } => transfer(Reserved, Allocated, amount),
+        &ReservationCancelled(_, amount) => transfer(Reserved, Available, amount),
+        &ReservationExpired { amount_freed, .. } => {
+            let mut p = transfer(Reserved, Expired, amount_freed);
+            p.extend(transfer(Expired, Available, amount_freed));
+            p
+        },
+        &AllocationFreed { amount, .. } => transfer(Allocated, Available, amount),
+        &ReservationSpent { amount, .. } => transfer(Reserved, Spent, amount),
+        &SpendingRefunded { amount, .. } => transfer(Spent, Available, amount),
+        _ => vec![]
+    }
+}
+
+// Catches the ledger up with the event log. Postings and the new checkpoint
+// are written in one transaction, so a run can be interrupted at any point.
+pub fn project(pool: &MyPool) -> Result<usize, CreditError> {
+    let conn = pool.get()?;
+    let trx = conn.transaction()?;
+
+    let (checkpoint, events) = events_after_checkpoint(&trx, PROJECTION)?;
+
+    let insert = trx.prepare(
+        "insert into ledger (event_id, aggregate_id, credit_type, account, reference, debit, credit)
+        values ($1, $2, $3, $4, $5, $6, $7)")?;
+    let mut types: HashMap<(i64, Uuid), CreditType> = HashMap::new();
+    let mut last = checkpoint;
+
+    for e in events.iter() {
+        let (event_id, aggregate_id) = (e.id, e.aggregate_id);
+        let evt: CreditEvent = serde_json::from_str(&e.payload)?;
+        last = e.checkpoint();
+
+        let reference = reference(&evt);
+        let credit_type = match evt {
+            CreditsAdded { ref credit_type, .. } |
+            CreditsRemoved { ref credit_type, .. } |
+            CreditsReserved { ref credit_type, .. } => credit_type.clone(),
+            _ => match reference {
+                Some(id) => match types.get(&(aggregate_id, id)).cloned() {
+                    Some(t) => t,
+                    None => match reserved_credit_type(&trx, aggregate_id, id) {
+                        Ok(t) => t,
+                        // the ledger is missing the reservation, so this and
+                        // every event after it wait for it to be put right
+                        Err(e) => {
+                            error!(event = event_id, account = aggregate_id, reservation = %id,
+                                "no credit type known for reservation");
+                            return Err(e)
+                        }
+                    }
+                },
+                None => continue
+            }
+        };
+        if let Some(id) = reference {
+            types.insert((aggregate_id, id), credit_type.clone());
+        }
+
+        let reference = reference.map(|id| id.to_string());
+        for p in postings(&evt) {
+            insert.execute(&[&event_id, &aggregate_id, &credit_type, &p.account.name(), &reference, &p.debit, &p.credit])?;
+        }
+    }
+
+    save_checkpoint(&trx, PROJECTION, last)?;
+    trx.commit()?;
+
+    let count = events.len();
+    if count > 0 {
+        debug!(events = count, "projected into the ledger");
+    }
+    Ok(count)
+}
+
+// credit type of a reservation, as recorded when it was posted
+fn reserved_credit_type(trx: &Transaction, aggregate_id: i64, id: Uuid) -> Result<CreditType, CreditError> {
+    let rows = trx.query(
+        "select credit_type from ledger where aggregate_id = $1 and reference = $2 limit 1",
+        &[&aggregate_id, &id.to_string()])?;
+    if rows.is_empty() {
+        return Err(CreditError::ReservationNotFound)
+    }
+    Ok(rows.get(0).get(0))
+}
+
+// Sums postings per ledger account and checks that debits equal credits for
+// every contract and for the ledger as a whole
+pub fn trial_balance(pool: &MyPool) -> Result<TrialBalance, CreditError> {
+    let conn = pool.get()?;
+    let mut tb = TrialBalance::default();
+
+    let rows = conn.query(
+        "select aggregate_id, credit_type, account, sum(debit)::bigint, sum(credit)::bigint from ledger
+        group by aggregate_id, credit_type, account
+        order by aggregate_id, credit_type, account", &[])?;
+    for row in rows.iter() {
+        let balance = LedgerBalance {
+            aggregate_id: row.get(0),
+            credit_type: row.get(1),
+            account: row.get(2),
+            debit: row.get(3),
+            credit: row.get(4)
+        };
+        tb.total_debit += balance.debit;
+        tb.total_credit += balance.credit;
+        tb.accounts.push(balance);
+    }
+
+    let rows = conn.query(
+        "select aggregate_id from ledger group by aggregate_id
+        having sum(debit) <> sum(credit) order by aggregate_id", &[])?;
+    tb.unbalanced = rows.iter().map(|row| row.get(0)).collect();
+
+    Ok(tb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+    use chrono::Utc;
+    use eventstore::test_pool;
+    use default_credit_type;
+
+    fn balanced(evt: CreditEvent) -> bool {
+        let p = postings(&evt);
+        p.iter().map(|p| p.debit).sum::<Amount>() == p.iter().map(|p| p.credit).sum::<Amount>()
+    }
+
+    #[test]
+    fn every_posting_is_balanced() {
+        let id = Uuid::new_v4();
+        assert!(balanced(CreditsAdded { credit_type: default_credit_type(), amount: 10 }));
+        assert!(balanced(CreditsReserved { id, credit_type: default_credit_type(), amount: 5, timestamp: Utc::now() }));
+        assert!(balanced(ReservationExpired { id, amount_freed: 5, available: 10 }));
+        assert!(balanced(ReservationSpent { id, amount: 5 }));
+        assert!(balanced(SpendingRefunded { id, amount: 2 }));
+    }
+
+    #[test]
+    fn adding_credits_moves_from_issued_to_available() {
+        let p = postings(&CreditsAdded { credit_type: default_credit_type(), amount: 10 });
+        assert_eq!(p, vec![
+            Posting { account: LedgerAccount::Available, debit: 10, credit: 0 },
+            Posting { account: LedgerAccount::Issued, debit: 0, credit: 10 }
+        ]);
+    }
+
+    #[test]
+    fn settings_are_not_posted() {
+        assert!(postings(&CreditLimitSet { credit_type: default_credit_type(), limit: 10 }).is_empty());
+    }
+
+    fn insert_added(trx: &Transaction, aggregate_id: i64) {
+        let payload = serde_json::to_string(&CreditsAdded { credit_type: default_credit_type(), amount: 10 }).unwrap();
+        trx.execute("insert into events (aggregate_type, aggregate_id, payload, version) values ($1, $2, $3, 1)",
+            &[&Contract::TYPE, &aggregate_id, &payload]).unwrap();
+    }
+
+    fn posted(aggregate_id: i64, pool: &MyPool) -> bool {
+        let conn = pool.get().unwrap();
+        !conn.query("select 1 from ledger where aggregate_id = $1", &[&aggregate_id]).unwrap().is_empty()
+    }
+
+    #[test]
+    #[ignore]
+    fn it_waits_for_events_committed_out_of_order() {
+        let pool = test_pool();
+        let (slow, fast) = (Utc::now().timestamp_nanos() / 1000, Utc::now().timestamp_nanos() / 1000 + 1);
+        let (slow_conn, fast_conn) = (pool.get().unwrap(), pool.get().unwrap());
+
+        let slow_trx = slow_conn.transaction().unwrap();
+        insert_added(&slow_trx, slow);
+        let fast_trx = fast_conn.transaction().unwrap();
+        insert_added(&fast_trx, fast);
+        fast_trx.commit().unwrap();
+
+        project(&pool).unwrap();
+        assert!(!posted(fast, &pool), "should wait for the transaction that started first");
+
+        slow_trx.commit().unwrap();
+        // other tests' transactions may hold it back a little longer
+        for _ in 0..100 {
+            project(&pool).unwrap();
+            if posted(fast, &pool) {
+                break
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(posted(slow, &pool));
+        assert!(posted(fast, &pool));
+    }
+}
@@ -0,0 +1,1065 @@
+extern crate serde;
+extern crate serde_json;
+extern crate uuid;
+extern crate postgres;
+extern crate r2d2;
+extern crate r2d2_postgres;
+extern crate chrono;
+extern crate dotenv;
+#[macro_use]
+extern crate tracing;
+extern crate tracing_subscriber;
+#[macro_use]
+extern crate serde_derive;
+extern crate grpcio;
+extern crate protobuf;
+extern crate futures;
+extern crate tiny_http;
+extern crate toml;
+extern crate signal_hook;
+extern crate hmac;
+extern crate sha2;
+extern crate base64;
+#[cfg(feature = "tls")]
+extern crate openssl;
+#[macro_use]
+extern crate prometheus;
+#[macro_use]
+extern crate lazy_static;
+
+mod credits;
+mod credits_grpc;
+mod health;
+mod health_grpc;
+mod reflection;
+mod reflection_grpc;
+
+use std::collections::{HashMap, VecDeque};
+use uuid::prelude::*;
+use chrono::prelude::*;
+use chrono::Duration;
+use r2d2::Pool;
+use r2d2_postgres::PostgresConnectionManager;
+use CreditEvent::*;
+
+pub mod api;
+pub mod server;
+pub mod rest;
+pub mod eventstore;
+pub mod transfer;
+pub mod person;
+pub mod ledger;
+pub mod audit;
+pub mod export;
+pub mod migrations;
+pub mod metrics;
+pub mod logging;
+pub mod config;
+pub mod shutdown;
+pub mod healthcheck;
+pub mod discovery;
+pub mod auth;
+pub mod tls;
+pub mod alerts;
+pub mod webhooks;
+
+type R = Result<Vec<CreditEvent>, CreditError>;
+pub type MyPool = Pool<PostgresConnectionManager>;
+type Ts = DateTime<Utc>;
+
+pub trait Aggregate {
+    type Item;
+    type Cmd;
+    type Error;
+
+    // tag telling aggregates apart in the event store
+    const TYPE: &'static str;
+
+    fn new(id: i64) -> Self;
+    fn id(&self) -> i64;
+    fn version(&self) -> i64;
+    fn handle(&self, cmd: &Self::Cmd) -> Result<Vec<Self::Item>, Self::Error>;
+    fn apply(&mut self, evt: &Self::Item) -> ();
+}
+
+#[derive(Default, Debug, Serialize, Deserialize)]
+#[serde(from = "StoredContract")]
+pub struct Contract {
+    id: i64,
+    version: i64,
+    #[serde(default)]
+    balances: HashMap<CreditType, CreditBalance>,
+    reservations: HashMap<Uuid, CreditReservation>,
+    allocations: HashMap<Uuid, CreditReservation>,
+    #[serde(default)]
+    spendings: HashMap<Uuid, CreditReservation>,
+    // how far below zero reservations may take the balance, per type
+    #[serde(default)]
+    credit_limits: HashMap<CreditType, Amount>,
+    #[serde(default)]
+    state: AccountState,
+    #[serde(default)]
+    owner: Option<PersonId>,
+    #[serde(default)]
+    quota: Option<Quota>,
+    // when credits were reserved within the quota's window, oldest first
+    #[serde(default)]
+    quota_usage: VecDeque<(Ts, Amount)>,
+    // credits available below which the owner wants to hear about it, per type
+    #[serde(default)]
+    balance_alerts: HashMap<CreditType, Amount>,
+}
+
+// Most credits, of any type, that may be reserved within a window of time
+// regardless of the balance
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Quota {
+    pub amount: Amount,
+    // seconds
+    pub window: i64
+}
+
+// Contract as found in snapshots, which before credit types held the balance
+// of what is now the default type in amount and spent
+#[derive(Deserialize)]
+struct StoredContract {
+    id: i64,
+    version: i64,
+    #[serde(default)]
+    balances: HashMap<CreditType, CreditBalance>,
+    amount: Option<Amount>,
+    spent: Option<Amount>,
+    reservations: HashMap<Uuid, CreditReservation>,
+    allocations: HashMap<Uuid, CreditReservation>,
+    #[serde(default)]
+    spendings: HashMap<Uuid, CreditReservation>,
+    #[serde(default)]
+    credit_limits: HashMap<CreditType, Amount>,
+    #[serde(default)]
+    state: AccountState,
+    #[serde(default)]
+    owner: Option<PersonId>,
+    #[serde(default)]
+    quota: Option<Quota>,
+    #[serde(default)]
+    quota_usage: VecDeque<(Ts, Amount)>,
+    #[serde(default)]
+    balance_alerts: HashMap<CreditType, Amount>,
+}
+
+impl From<StoredContract> for Contract {
+    fn from(s: StoredContract) -> Self {
+        let mut balances = s.balances;
+        if s.amount.is_some() || s.spent.is_some() {
+            balances.entry(default_credit_type()).or_insert(CreditBalance {
+                amount: s.amount.unwrap_or(0),
+                spent: s.spent.unwrap_or(0)
+            });
+        }
+        Contract {
+            id: s.id,
+            version: s.version,
+            balances,
+            reservations: s.reservations,
+            allocations: s.allocations,
+            spendings: s.spendings,
+            credit_limits: s.credit_limits,
+            state: s.state,
+            owner: s.owner,
+            quota: s.quota,
+            quota_usage: s.quota_usage,
+            balance_alerts: s.balance_alerts
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AccountState {
+    Open,
+    // No new reservations until unfrozen
+    Frozen,
+    // Nothing more can happen to the account
+    Closed
+}
+
+impl Default for AccountState {
+    fn default() -> Self {
+        AccountState::Open
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreditBalance {
+    amount: Amount,
+    spent: Amount,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreditReservation {
+    pub amount: Amount,
+    #[serde(default = "default_credit_type")]
+    pub credit_type: CreditType,
+    pub created_time: Ts,
+    pub allocated_time: Option<Ts>
+}
+
+#[derive(Debug)]
+pub enum CreditError {
+    NotEnoughMoney {has: Amount, needs: Amount},
+    InvalidAmount(Amount),
+    ReservationAlreadyExists,
+    ReservationNotFound,
+    AllocationNotFound,
+    SpendingNotFound,
+    RefundTooLarge {refundable: Amount, requested: Amount},
+    TransferToSelf,
+    InvalidRequest(String),
+    AccountFrozen,
+    AccountClosed,
+    ContractAlreadyOwned,
+    ContractNotAttached,
+    ReservationLimitExceeded {limit: Amount, needs: Amount},
+    // retry_after is in seconds, None if the amount is more than the quota
+    QuotaExceeded {limit: Amount, used: Amount, retry_after: Option<i64>},
+    InvalidReservationId(String),
+    InvalidImport(String),
+    Unauthenticated(String),
+    PermissionDenied,
+
+    ConcurrencyError,
+    StorageError(postgres::error::Error),
+    // no connection could be had from the pool in time
+    PoolError(r2d2::Error),
+    DataError(serde_json::Error)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum CreditEvent {
+    #[serde(deserialize_with = "deserialize_credits_added")]
+    CreditsAdded {
+        credit_type: CreditType,
+        amount: Amount
+    },
+    CreditsRemoved {
+        credit_type: CreditType,
+        amount: Amount,
+        reason: String
+    },
+    CreditsReserved { 
+        id: Uuid,
+        #[serde(default = "default_credit_type")]
+        credit_type: CreditType,
+        amount: Amount,
+        timestamp: Ts
+    },
+    CreditsAllocated {
+        id: Uuid,
+        amount: Amount,
+        timestamp: Ts
+    },
+    ReservationCancelled(Uuid, Amount),
+    ReservationExpired {
+        id: Uuid,
+        amount_freed: Amount,
+        available: Amount
+    },
+    AllocationFreed {
+        id: Uuid,
+        amount: Amount,
+        available: Amount
+    },
+    ReservationSpent {
+        id: Uuid,
+        amount: Amount
+    },
+    SpendingRefunded {
+        id: Uuid,
+        amount: Amount
+    },
+    CreditLimitSet { credit_type: CreditType, limit: Amount },
+    AccountFrozen,
+    AccountUnfrozen,
+    AccountClosed,
+    OwnerAssigned(PersonId),
+    ReservationQuotaSet(Option<Quota>),
+    BalanceAlertSet { credit_type: CreditType, threshold: Option<Amount> },
+    BalanceThresholdCrossed { credit_type: CreditType, threshold: Amount, available: Amount }
+}
+
+pub type PersonId = i64;
+pub type Amount = i64;
+pub type CreditType = String;
+
+// events written before credit types existed belong to this one
+pub const DEFAULT_CREDIT_TYPE: &str = "default";
+
+pub fn default_credit_type() -> CreditType {
+    DEFAULT_CREDIT_TYPE.to_string()
+}
+
+// CreditsAdded used to hold nothing but the amount
+fn deserialize_credits_added<'de, D>(deserializer: D) -> Result<(CreditType, Amount), D::Error>
+    where D: serde::Deserializer<'de>
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Added {
+        Untyped(Amount),
+        Typed { credit_type: CreditType, amount: Amount }
+    }
+
+    match serde::Deserialize::deserialize(deserializer)? {
+        Added::Untyped(amount) => Ok((default_credit_type(), amount)),
+        Added::Typed { credit_type, amount } => Ok((credit_type, amount))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum CreditCommand {
+    // Add credits of a type to account
+    AddCredits(CreditType, Amount),
+    // Remove credits of a type from account, stating why
+    RemoveCredits(CreditType, Amount, String),
+    // Reserve an amount of credits of a type
+    ReserveCredits(Uuid, CreditType, Amount),
+    // Allocate credits reserved with a reservation
+    AllocateCredits(Uuid),
+    // Cancel a reservation made previously
+    CancelReservation(Uuid),
+    // Clean up expired reservations older than the provided number of seconds
+    EvictExpiredReservations(i64),
+    // Free an allocation made previously
+    FreeAllocation(Uuid),
+    // Permanently spend credits
+    SpendReservation(Uuid),
+    // Return all or part of a spent reservation to the account
+    RefundSpend(Uuid, Amount),
+    // Allow reservations to take the balance of a type this far below zero
+    SetCreditLimit(CreditType, Amount),
+    // Suspend the account, rejecting new reservations
+    FreezeAccount,
+    // Lift a suspension made previously
+    UnfreezeAccount,
+    // Permanently close the account
+    CloseAccount,
+    // Make the account part of a customer
+    AssignOwner(PersonId),
+    // Cap the credits reserved within a window of time, or lift the cap
+    SetReservationQuota(Option<Quota>),
+    // Be alerted when available credits of a type drop below a threshold, or stop
+    SetBalanceAlert(CreditType, Option<Amount>)
+}
+
+impl Contract {
+
+    // reservations not yet allocated, spent or cancelled
+    pub fn reservations(&self) -> &HashMap<Uuid, CreditReservation> {
+        &self.reservations
+    }
+
+    // balance of a single credit type, zero for types never added
+    fn balance(&self, credit_type: &str) -> CreditBalance {
+        self.balances.get(credit_type).cloned().unwrap_or_default()
+    }
+
+    fn balance_mut(&mut self, credit_type: &str) -> &mut CreditBalance {
+        self.balances.entry(credit_type.to_string()).or_insert_with(CreditBalance::default)
+    }
+
+    fn add_credits(&self, credit_type: &str, amount: i64) -> R {
+        if amount <= 0 {
+            return Err(CreditError::InvalidAmount(amount))
+        }
+
+        Ok(vec![CreditsAdded { credit_type: credit_type.to_string(), amount }])
+    }
+
+    fn remove_credits(&self, credit_type: &str, amount: i64, reason: &str) -> R {
+        if amount <= 0 {
+            return Err(CreditError::InvalidAmount(amount))
+        }
+
+        let has = self.balance(credit_type).amount;
+        if has-amount < 0 {
+            return Err(CreditError::NotEnoughMoney {has, needs: amount-has })
+        }
+
+        let mut evts = vec![CreditsRemoved { credit_type: credit_type.to_string(), amount, reason: reason.to_string() }];
+        evts.extend(self.threshold_crossed(credit_type, amount));
+        Ok(evts)
+    }
+
+    // credits of a type currently held by reservations and allocations
+    fn reserved(&self, credit_type: &str) -> Amount {
+        self.reservations.values()
+            .chain(self.allocations.values())
+            .filter(|r| r.credit_type == credit_type)
+            .map(|r| r.amount)
+            .sum()
+    }
+
+    // credits that can be reserved, including what the credit limit allows
+    fn available(&self, credit_type: &str) -> Amount {
+        self.balance(credit_type).amount + self.credit_limits.get(credit_type).cloned().unwrap_or(0)
+    }
+
+    // Alert if taking an amount out of the balance takes it from at or above the
+    // alert's threshold to below it. The balance has reservations taken out but
+    // leaves the credit limit aside, so the alert warns of credits actually
+    // running out rather than of borrowing running out. Spending a reservation
+    // leaves it as it is, the credits having left when they were reserved.
+    fn threshold_crossed(&self, credit_type: &str, amount: Amount) -> Option<CreditEvent> {
+        let threshold = *self.balance_alerts.get(credit_type)?;
+        let before = self.balance(credit_type).amount;
+        let available = before - amount;
+        if before >= threshold && available < threshold {
+            Some(BalanceThresholdCrossed { credit_type: credit_type.to_string(), threshold, available })
+        } else {
+            None
+        }
+    }
+
+    // Credits reserved within the quota's window, and how many seconds until
+    // enough of them have left it for another amount to fit
+    fn quota_used(&self, quota: &Quota, amount: Amount, now: Ts) -> (Amount, Option<i64>) {
+        let window = Duration::seconds(quota.window);
+        let recent: Vec<&(Ts, Amount)> = self.quota_usage.iter().filter(|&&(ts, _)| ts + window > now).collect();
+        let used: Amount = recent.iter().map(|&&(_, a)| a).sum();
+        if amount > quota.amount {
+            return (used, None)
+        }
+        let mut left = used;
+        for &&(ts, a) in recent.iter() {
+            if left + amount <= quota.amount {
+                break
+            }
+            left -= a;
+            if left + amount <= quota.amount {
+                return (used, Some((ts + window - now).num_seconds().max(1)))
+            }
+        }
+        (used, Some(0))
+    }
+
+    fn reserve_credits(&self, credit_type: &str, amount: i64, id: Uuid) -> R {
+        if amount <= 0 {
+            return Err(CreditError::InvalidAmount(amount))
+        }
+
+        if self.state == AccountState::Frozen {
+            return Err(CreditError::AccountFrozen)
+        }
+
+        let now = Utc::now();
+        if let Some(ref quota) = self.quota {
+            let (used, retry_after) = self.quota_used(quota, amount, now);
+            if used + amount > quota.amount {
+                return Err(CreditError::QuotaExceeded { limit: quota.amount, used, retry_after })
+            }
+        }
+
+        let available = self.available(credit_type);
+        if available-amount < 0 {
+            return Err(CreditError::NotEnoughMoney {has: available, needs: amount-available })
+        }
+
+        if self.reservations.contains_key(&id) {
+            return Err(CreditError::ReservationAlreadyExists)
+        }
+
+        let mut evts = vec![CreditsReserved { amount, credit_type: credit_type.to_string(), id, timestamp: now }];
+        evts.extend(self.threshold_crossed(credit_type, amount));
+        Ok(evts)
+    }
+
+    fn allocate_credits(&self, id: Uuid) -> R {
+        match self.reservations.get(&id) {
+            Some(res) => Ok(vec![CreditsAllocated {
+                id,
+                amount: res.amount,
+                timestamp: Utc::now()
+            }]),
+            None => Err(CreditError::ReservationNotFound)
+        }
+    }
+
+    fn cancel_reservation(&self, id: Uuid) -> R {
+        match self.reservations.get(&id) {
+            Some(res) => Ok(vec![ReservationCancelled(id, res.amount)]),
+            None => Err(CreditError::ReservationNotFound)
+        }
+    }
+
+    fn evict_expired_resevations(&self, age: i64) -> R {
+        let now = Utc::now();
+        let mut total_freed: HashMap<&str, Amount> = HashMap::new();
+        let dur = Duration::seconds(age);
+        let events: Vec<CreditEvent> = self.reservations.iter().filter_map(|(id, r)| {
+            if (r.created_time+dur) < now {
+                let freed = total_freed.entry(&r.credit_type).or_insert(0);
+                *freed += r.amount;
+                Some(ReservationExpired {
+                    id: *id,
+                    amount_freed: r.amount,
+                    available: self.balance(&r.credit_type).amount + *freed
+                })
+            } else {
+                None
+            }
+        }).collect();
+        
+        Ok(events)
+    }
+
+    fn free_allocation(&self, id: Uuid) -> R {
+        if let Some(res) = self.allocations.get(&id) {
+            return Ok(vec![AllocationFreed {
+                id,
+                amount: res.amount,
+                available: self.balance(&res.credit_type).amount + res.amount
+            }]);
+        }
+
+        Err(CreditError::AllocationNotFound)
+    }
+
+    fn spend_reservation(&self, id: Uuid) -> R {
+        match self.reservations.get(&id) {
+            Some(res) => Ok(vec![ReservationSpent {
+                id,
+                amount: res.amount
+            }]),
+            None => Err(CreditError::ReservationNotFound)
+        }
+    }
+
+    fn refund_spend(&self, id: Uuid, amount: i64) -> R {
+        if amount <= 0 {
+            return Err(CreditError::InvalidAmount(amount))
+        }
+
+        match self.spendings.get(&id) {
+            Some(res) if res.amount < amount => Err(CreditError::RefundTooLarge {
+                refundable: res.amount,
+                requested: amount
+            }),
+            Some(_) => Ok(vec![SpendingRefunded { id, amount }]),
+            None => Err(CreditError::SpendingNotFound)
+        }
+    }
+
+    fn set_credit_limit(&self, credit_type: &str, limit: i64) -> R {
+        if limit < 0 {
+            return Err(CreditError::InvalidAmount(limit))
+        }
+
+        Ok(vec![CreditLimitSet { credit_type: credit_type.to_string(), limit }])
+    }
+
+    fn freeze_account(&self) -> R {
+        match self.state {
+            AccountState::Frozen => Ok(vec![]),
+            _ => Ok(vec![AccountFrozen])
+        }
+    }
+
+    fn unfreeze_account(&self) -> R {
+        match self.state {
+            AccountState::Frozen => Ok(vec![AccountUnfrozen]),
+            _ => Ok(vec![])
+        }
+    }
+
+    fn close_account(&self) -> R {
+        Ok(vec![AccountClosed])
+    }
+
+    fn assign_owner(&self, owner: PersonId) -> R {
+        match self.owner {
+            Some(o) if o == owner => Ok(vec![]),
+            Some(_) => Err(CreditError::ContractAlreadyOwned),
+            None => Ok(vec![OwnerAssigned(owner)])
+        }
+    }
+
+    fn set_reservation_quota(&self, quota: Option<Quota>) -> R {
+        if let Some(q) = quota {
+            if q.amount < 0 {
+                return Err(CreditError::InvalidAmount(q.amount))
+            }
+            if q.window <= 0 {
+                return Err(CreditError::InvalidRequest("quota window must be positive".to_string()))
+            }
+        }
+
+        Ok(vec![ReservationQuotaSet(quota)])
+    }
+
+    fn set_balance_alert(&self, credit_type: &str, threshold: Option<Amount>) -> R {
+        Ok(vec![BalanceAlertSet { credit_type: credit_type.to_string(), threshold }])
+    }
+}
+
+impl Aggregate for Contract {
+    type Item = CreditEvent;
+    type Cmd = CreditCommand;
+    type Error = CreditError;
+
+    const TYPE: &'static str = "contract";
+
+    fn new(id: i64) -> Self {
+        Contract { id, ..Default::default() }
+    }
+
+    fn version(&self) -> i64 {
+        self.version
+    }
+
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn handle(&self, cmd: &Self::Cmd) -> Result<Vec<Self::Item>, Self::Error> {
+        if self.state == AccountState::Closed {
+            return Err(CreditError::AccountClosed)
+        }
+
+        match cmd {
+            &CreditCommand::AddCredits(ref t, amt) => self.add_credits(t, amt),
+            &CreditCommand::RemoveCredits(ref t, amt, ref reason) => self.remove_credits(t, amt, reason),
+            &CreditCommand::ReserveCredits(id, ref t, amt) => self.reserve_credits(t, amt, id),
+            &CreditCommand::AllocateCredits(id) => self.allocate_credits(id),
+            &CreditCommand::CancelReservation(id) => self.cancel_reservation(id),
+            &CreditCommand::EvictExpiredReservations(age) => self.evict_expired_resevations(age),
+            &CreditCommand::FreeAllocation(id) => self.free_allocation(id),
+            &CreditCommand::SpendReservation(id) => self.spend_reservation(id),
+            &CreditCommand::RefundSpend(id, amt) => self.refund_spend(id, amt),
+            &CreditCommand::SetCreditLimit(ref t, limit) => self.set_credit_limit(t, limit),
+            &CreditCommand::FreezeAccount => self.freeze_account(),
+            &CreditCommand::UnfreezeAccount => self.unfreeze_account(),
+            &CreditCommand::CloseAccount => self.close_account(),
+            &CreditCommand::AssignOwner(owner) => self.assign_owner(owner),
+            &CreditCommand::SetReservationQuota(quota) => self.set_reservation_quota(quota),
+            &CreditCommand::SetBalanceAlert(ref t, threshold) => self.set_balance_alert(t, threshold)
+        }
+    }
+
+    fn apply(&mut self, evt: &Self::Item) -> () {
+        self.version += 1;
+
+        match evt {
+            &CreditsAdded { ref credit_type, amount } => self.balance_mut(credit_type).amount += amount,
+            &CreditsRemoved { ref credit_type, amount, reason: _ } => self.balance_mut(credit_type).amount -= amount,
+            &CreditsReserved { id, ref credit_type, amount, timestamp } => {
+                self.reservations.insert(id, CreditReservation {
+                    amount,
+                    credit_type: credit_type.clone(),
+                    created_time: timestamp,
+                    allocated_time: None
+                });
+                self.balance_mut(credit_type).amount -= amount;
+                // only reservations made while there is a quota count towards it
+                if let Some(quota) = self.quota {
+                    let window = Duration::seconds(quota.window);
+                    while self.quota_usage.front().is_some_and(|&(ts, _)| ts + window <= timestamp) {
+                        self.quota_usage.pop_front();
+                    }
+                    self.quota_usage.push_back((timestamp, amount));
+                }
+            },
+            &CreditsAllocated {id, amount: _, timestamp} => {
+                let mut res = self.reservations.remove(&id).unwrap();
+                res.allocated_time = Some(timestamp);
+                self.allocations.insert(id, res);
+            },
+            &ReservationCancelled(id, amount) => {
+                let res = self.reservations.remove(&id).unwrap();
+                self.balance_mut(&res.credit_type).amount += amount;
+            },
+            &ReservationExpired {id, amount_freed, available: _} => {
+                let res = self.reservations.remove(&id).unwrap();
+                self.balance_mut(&res.credit_type).amount += amount_freed;
+            },
+            &AllocationFreed {id, amount, available: _} => {
+                let res = self.allocations.remove(&id).unwrap();
+                self.balance_mut(&res.credit_type).amount += amount;
+            },
+            &ReservationSpent {id, amount: amt } => {
+                let res = self.reservations.remove(&id).unwrap();
+                self.balance_mut(&res.credit_type).spent += amt;
+                self.spendings.insert(id, res);
+            },
+            &SpendingRefunded {id, amount} => {
+                let credit_type = {
+                    let res = self.spendings.get_mut(&id).unwrap();
+                    res.amount -= amount;
+                    res.credit_type.clone()
+                };
+                let balance = self.balance_mut(&credit_type);
+                balance.spent -= amount;
+                balance.amount += amount;
+            },
+            &CreditLimitSet { ref credit_type, limit } => { self.credit_limits.insert(credit_type.clone(), limit); },
+            AccountFrozen => self.state = AccountState::Frozen,
+            AccountUnfrozen => self.state = AccountState::Open,
+            AccountClosed => self.state = AccountState::Closed,
+            &OwnerAssigned(owner) => self.owner = Some(owner),
+            &ReservationQuotaSet(quota) => {
+                if quota.is_none() {
+                    self.quota_usage.clear();
+                }
+                self.quota = quota;
+            },
+            &BalanceAlertSet { ref credit_type, threshold } => match threshold {
+                Some(t) => { self.balance_alerts.insert(credit_type.clone(), t); },
+                None => { self.balance_alerts.remove(credit_type); }
+            },
+            &BalanceThresholdCrossed { .. } => {}
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eventstore::run_cmd;
+
+    #[test]
+    fn it_is_empty_by_default() {
+        let c = Contract::default();
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).amount, 0);
+        assert_eq!(c.id, 0);
+    }
+
+    #[test]
+    fn it_updates_amount() {
+        let c = with_amount(10);
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).amount, 10);
+    }
+
+    #[test]
+    fn it_reserves_amount() {
+        let mut c = with_amount(10);
+        let id = Uuid::new_v4();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(id, default_credit_type(), 5)).unwrap();
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).amount, 5);
+    }
+
+    #[test]
+    fn it_cannot_reserve_too_much() {
+        let mut c = with_amount(10);
+        let id = Uuid::new_v4();
+        let r = run_cmd(&mut c, CreditCommand::ReserveCredits(id, default_credit_type(), 20));
+        r.expect_err("should error out");
+    }
+
+    #[test]
+    fn it_spends_what_is_available() {
+        let mut c = with_amount(10);
+        let id = Uuid::new_v4();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(id, default_credit_type(), 10)).unwrap();
+        run_cmd(&mut c, CreditCommand::SpendReservation(id)).unwrap();
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).amount, 0);
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).spent, 10);
+    }
+
+    #[test]
+    fn it_cannot_respend() {
+        let mut c = with_amount(10);
+        let id = Uuid::new_v4();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(id, default_credit_type(), 5)).unwrap();
+        run_cmd(&mut c, CreditCommand::SpendReservation(id)).unwrap();
+        run_cmd(&mut c, CreditCommand::SpendReservation(id))
+            .expect_err("should not allow respend");
+    }
+
+    #[test]
+    fn it_frees_reservation() {
+        let mut c = with_amount(10);
+        let id = Uuid::new_v4();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(id, default_credit_type(), 5)).unwrap();
+        run_cmd(&mut c, CreditCommand::CancelReservation(id)).unwrap();
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).amount, 10);
+    }
+
+    #[test]
+    fn cancelled_reservation_cannot_be_allocated() {
+        let mut c = with_amount(10);
+        let id = Uuid::new_v4();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(id, default_credit_type(), 5)).unwrap();
+        run_cmd(&mut c, CreditCommand::CancelReservation(id)).unwrap();
+        run_cmd(&mut c, CreditCommand::AllocateCredits(id))
+            .expect_err("should not allow allocation");        
+    }
+
+    #[test]
+    fn spent_reservation_cannot_be_allocated() {
+        let mut c = with_amount(10);
+        let id = Uuid::new_v4();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(id, default_credit_type(), 5)).unwrap();
+        run_cmd(&mut c, CreditCommand::SpendReservation(id)).unwrap();
+        run_cmd(&mut c, CreditCommand::AllocateCredits(id))
+            .expect_err("should not allow allocation");        
+    }
+
+    #[test]
+    fn it_cannot_add_negative_amount() {
+        let mut c = with_amount(10);
+        run_cmd(&mut c, CreditCommand::AddCredits(default_credit_type(), -5))
+            .expect_err("should not allow negative amount");
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).amount, 10);
+    }
+
+    #[test]
+    fn it_removes_credits() {
+        let mut c = with_amount(10);
+        run_cmd(&mut c, CreditCommand::RemoveCredits(default_credit_type(), 4, "chargeback".to_string())).unwrap();
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).amount, 6);
+    }
+
+    #[test]
+    fn it_cannot_remove_too_much() {
+        let mut c = with_amount(10);
+        run_cmd(&mut c, CreditCommand::RemoveCredits(default_credit_type(), 20, "chargeback".to_string()))
+            .expect_err("should not allow removing more than available");
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).amount, 10);
+    }
+
+    #[test]
+    fn it_refunds_part_of_spending() {
+        let mut c = with_amount(10);
+        let id = Uuid::new_v4();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(id, default_credit_type(), 8)).unwrap();
+        run_cmd(&mut c, CreditCommand::SpendReservation(id)).unwrap();
+        run_cmd(&mut c, CreditCommand::RefundSpend(id, 3)).unwrap();
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).amount, 5);
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).spent, 5);
+    }
+
+    #[test]
+    fn it_cannot_refund_more_than_spent() {
+        let mut c = with_amount(10);
+        let id = Uuid::new_v4();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(id, default_credit_type(), 8)).unwrap();
+        run_cmd(&mut c, CreditCommand::SpendReservation(id)).unwrap();
+        run_cmd(&mut c, CreditCommand::RefundSpend(id, 5)).unwrap();
+        run_cmd(&mut c, CreditCommand::RefundSpend(id, 5))
+            .expect_err("should not allow refunding more than spent");
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).spent, 3);
+    }
+
+    #[test]
+    fn unspent_reservation_cannot_be_refunded() {
+        let mut c = with_amount(10);
+        let id = Uuid::new_v4();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(id, default_credit_type(), 5)).unwrap();
+        run_cmd(&mut c, CreditCommand::RefundSpend(id, 5))
+            .expect_err("should not allow refunding a reservation");
+    }
+
+    #[test]
+    fn it_reserves_within_credit_limit() {
+        let mut c = with_amount(10);
+        run_cmd(&mut c, CreditCommand::SetCreditLimit(default_credit_type(), 5)).unwrap();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), default_credit_type(), 15)).unwrap();
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).amount, -5);
+        run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), default_credit_type(), 1))
+            .expect_err("should not reserve beyond credit limit");
+    }
+
+    #[test]
+    fn it_keeps_credit_limits_per_type() {
+        let mut c = with_amount(10);
+        run_cmd(&mut c, CreditCommand::SetCreditLimit(default_credit_type(), 5)).unwrap();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), "gpu".to_string(), 1))
+            .expect_err("should not lend a type without a limit of its own");
+        run_cmd(&mut c, CreditCommand::SetCreditLimit("gpu".to_string(), 2)).unwrap();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), "gpu".to_string(), 2)).unwrap();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), "gpu".to_string(), 1))
+            .expect_err("should not reserve beyond the type's credit limit");
+        run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), default_credit_type(), 15)).unwrap();
+    }
+
+    #[test]
+    fn it_cannot_set_negative_credit_limit() {
+        let mut c = with_amount(10);
+        run_cmd(&mut c, CreditCommand::SetCreditLimit(default_credit_type(), -5))
+            .expect_err("should not allow negative credit limit");
+    }
+
+    #[test]
+    fn frozen_account_cannot_reserve() {
+        let mut c = with_amount(10);
+        run_cmd(&mut c, CreditCommand::FreezeAccount).unwrap();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), default_credit_type(), 5))
+            .expect_err("should not reserve on frozen account");
+        run_cmd(&mut c, CreditCommand::UnfreezeAccount).unwrap();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), default_credit_type(), 5)).unwrap();
+    }
+
+    #[test]
+    fn frozen_account_can_cancel_reservation() {
+        let mut c = with_amount(10);
+        let id = Uuid::new_v4();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(id, default_credit_type(), 5)).unwrap();
+        run_cmd(&mut c, CreditCommand::FreezeAccount).unwrap();
+        run_cmd(&mut c, CreditCommand::CancelReservation(id)).unwrap();
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).amount, 10);
+    }
+
+    #[test]
+    fn closed_account_rejects_everything() {
+        let mut c = with_amount(10);
+        run_cmd(&mut c, CreditCommand::CloseAccount).unwrap();
+        run_cmd(&mut c, CreditCommand::AddCredits(default_credit_type(), 5))
+            .expect_err("should not add to closed account");
+        run_cmd(&mut c, CreditCommand::UnfreezeAccount)
+            .expect_err("should not reopen closed account");
+    }
+
+    #[test]
+    fn it_sums_reservations_and_allocations() {
+        let mut c = with_amount(10);
+        let id = Uuid::new_v4();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(id, default_credit_type(), 3)).unwrap();
+        run_cmd(&mut c, CreditCommand::AllocateCredits(id)).unwrap();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), default_credit_type(), 4)).unwrap();
+        run_cmd(&mut c, CreditCommand::AddCredits("gpu".to_string(), 5)).unwrap();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), "gpu".to_string(), 2)).unwrap();
+        assert_eq!(c.reserved(DEFAULT_CREDIT_TYPE), 7);
+        assert_eq!(c.reserved("gpu"), 2);
+    }
+
+    #[test]
+    fn contract_cannot_change_owner() {
+        let mut c = with_amount(10);
+        run_cmd(&mut c, CreditCommand::AssignOwner(1)).unwrap();
+        run_cmd(&mut c, CreditCommand::AssignOwner(1)).unwrap();
+        run_cmd(&mut c, CreditCommand::AssignOwner(2))
+            .expect_err("should not allow another owner");
+    }
+
+    #[test]
+    fn it_keeps_credit_types_apart() {
+        let mut c = with_amount(10);
+        run_cmd(&mut c, CreditCommand::AddCredits("api".to_string(), 3)).unwrap();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), "api".to_string(), 5))
+            .expect_err("should not reserve default credits for api");
+        let id = Uuid::new_v4();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(id, "api".to_string(), 2)).unwrap();
+        run_cmd(&mut c, CreditCommand::CancelReservation(id)).unwrap();
+        assert_eq!(c.balance("api").amount, 3);
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).amount, 10);
+    }
+
+    #[test]
+    fn it_reads_snapshots_from_before_credit_types() {
+        let c: Contract = serde_json::from_str(r#"{
+            "id": 5, "version": 3, "amount": 7, "spent": 2, "reservations": {}, "allocations": {}
+        }"#).unwrap();
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).amount, 7);
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).spent, 2);
+
+        let c: Contract = serde_json::from_str(&serde_json::to_string(&c).unwrap()).unwrap();
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).amount, 7);
+    }
+
+    #[test]
+    fn it_reads_untyped_credits_added() {
+        let evt: CreditEvent = serde_json::from_str(r#"{"CreditsAdded":10}"#).unwrap();
+        let mut c = Contract::default();
+        c.apply(&evt);
+        assert_eq!(c.balance(DEFAULT_CREDIT_TYPE).amount, 10);
+    }
+
+    #[test]
+    fn it_roundtrips_credits_added() {
+        let evt = CreditsAdded { credit_type: "api".to_string(), amount: 10 };
+        let ser = serde_json::to_string(&evt).unwrap();
+        let mut c = Contract::default();
+        c.apply(&serde_json::from_str(&ser).unwrap());
+        assert_eq!(c.balance("api").amount, 10);
+    }
+
+    #[test]
+    fn quota_caps_reservations_per_window() {
+        let mut c = with_amount(100);
+        run_cmd(&mut c, CreditCommand::SetReservationQuota(Some(Quota { amount: 10, window: 3600 }))).unwrap();
+        let id = Uuid::new_v4();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(id, default_credit_type(), 6)).unwrap();
+        run_cmd(&mut c, CreditCommand::CancelReservation(id)).unwrap();
+        match run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), default_credit_type(), 5)) {
+            Err(CreditError::QuotaExceeded { limit: 10, used: 6, retry_after: Some(s) }) => assert!(s > 3500 && s <= 3600),
+            other => panic!("should exceed the quota, got {:?}", other)
+        }
+        run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), default_credit_type(), 4)).unwrap();
+
+        run_cmd(&mut c, CreditCommand::SetReservationQuota(None)).unwrap();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), default_credit_type(), 20)).unwrap();
+    }
+
+    #[test]
+    fn quota_forgets_reservations_outside_the_window() {
+        let mut c = with_amount(100);
+        run_cmd(&mut c, CreditCommand::SetReservationQuota(Some(Quota { amount: 10, window: 3600 }))).unwrap();
+        c.apply(&CreditsReserved {
+            amount: 10, credit_type: default_credit_type(), id: Uuid::new_v4(), timestamp: Utc::now() - Duration::hours(2)
+        });
+        run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), default_credit_type(), 10)).unwrap();
+        match run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), default_credit_type(), 11)) {
+            Err(CreditError::QuotaExceeded { retry_after: None, .. }) => {},
+            other => panic!("should never fit the quota, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn it_rejects_reserving_nothing_before_checking_quota() {
+        let mut c = with_amount(10);
+        run_cmd(&mut c, CreditCommand::SetReservationQuota(Some(Quota { amount: 5, window: 60 }))).unwrap();
+        for &amount in [0, -3].iter() {
+            match run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), default_credit_type(), amount)) {
+                Err(CreditError::InvalidAmount(a)) if a == amount => {},
+                other => panic!("should reject amount {}, got {:?}", amount, other)
+            }
+        }
+        assert!(c.quota_usage.is_empty());
+    }
+
+    #[test]
+    fn it_alerts_when_available_drops_below_threshold() {
+        let mut c = with_amount(10);
+        run_cmd(&mut c, CreditCommand::SetBalanceAlert(default_credit_type(), Some(5))).unwrap();
+        let evts = run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), default_credit_type(), 4)).unwrap();
+        assert_eq!(evts.len(), 1);
+
+        let evts = run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), default_credit_type(), 2)).unwrap();
+        match evts.last() {
+            Some(&BalanceThresholdCrossed { threshold: 5, available: 4, .. }) => {},
+            other => panic!("should alert, got {:?}", other)
+        }
+        let evts = run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), default_credit_type(), 1)).unwrap();
+        assert_eq!(evts.len(), 1, "should alert only when crossing");
+
+        run_cmd(&mut c, CreditCommand::AddCredits(default_credit_type(), 10)).unwrap();
+        let evts = run_cmd(&mut c, CreditCommand::RemoveCredits(default_credit_type(), 12, "test".to_string())).unwrap();
+        assert_eq!(evts.len(), 2, "should alert again once back above");
+    }
+
+    #[test]
+    fn it_alerts_on_balance_leaving_credit_limit_aside() {
+        let mut c = with_amount(10);
+        run_cmd(&mut c, CreditCommand::SetCreditLimit(default_credit_type(), 100)).unwrap();
+        run_cmd(&mut c, CreditCommand::SetBalanceAlert(default_credit_type(), Some(5))).unwrap();
+        let evts = run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), default_credit_type(), 6)).unwrap();
+        match evts.last() {
+            Some(&BalanceThresholdCrossed { threshold: 5, available: 4, .. }) => {},
+            other => panic!("should alert despite the credit limit, got {:?}", other)
+        }
+    }
+
+    fn with_amount(amount: Amount) -> Contract {
+        let mut c = Contract::default();
+        run_cmd(&mut c, CreditCommand::AddCredits(default_credit_type(), amount)).unwrap();
+        c
+    }
+}
@@ -0,0 +1,22 @@
+use std::io;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::{fmt, EnvFilter};
+
+use config::Config;
+
+// Sets up log output for a binary. The json format writes one object per
+// line, carrying the fields of the spans each record was made in. Closing
+// spans are logged with their timings. Everything goes to stderr, leaving
+// stdout to the admin tool's output.
+pub fn init(config: &Config) {
+    let builder = fmt()
+        .with_env_filter(EnvFilter::new(&config.log_level))
+        .with_writer(io::stderr)
+        .with_span_events(FmtSpan::CLOSE);
+
+    if config.log_format == "json" {
+        builder.json().with_current_span(true).with_span_list(true).init();
+    } else {
+        builder.init();
+    }
+}
@@ -0,0 +1,51 @@
+use prometheus::{self, Encoder, HistogramVec, IntCounterVec, TextEncoder};
+
+use CreditError;
+
+lazy_static! {
+    pub static ref REQUEST_DURATION: HistogramVec = register_histogram_vec!(
+        "credits_request_duration_seconds",
+        "Time taken to handle a request, by api and method",
+        &["api", "method"]).unwrap();
+
+    pub static ref ERRORS: IntCounterVec = register_int_counter_vec!(
+        "credits_errors_total",
+        "Requests failed, by api and error",
+        &["api", "error"]).unwrap();
+
+    pub static ref CONCURRENCY_CONFLICTS: IntCounterVec = register_int_counter_vec!(
+        "credits_concurrency_conflicts_total",
+        "Saves rejected because another writer changed the aggregate first",
+        &["aggregate_type"]).unwrap();
+
+    pub static ref EVENTS_WRITTEN: IntCounterVec = register_int_counter_vec!(
+        "credits_events_written_total",
+        "Events written to the store",
+        &["aggregate_type"]).unwrap();
+
+    pub static ref SNAPSHOTS_WRITTEN: IntCounterVec = register_int_counter_vec!(
+        "credits_snapshots_written_total",
+        "Snapshots written to the store",
+        &["aggregate_type"]).unwrap();
+
+    pub static ref LOAD_DURATION: HistogramVec = register_histogram_vec!(
+        "credits_aggregate_load_duration_seconds",
+        "Time taken to load an aggregate from its snapshot and events",
+        &["aggregate_type"]).unwrap();
+
+    pub static ref EVENTS_REPLAYED: IntCounterVec = register_int_counter_vec!(
+        "credits_events_replayed_total",
+        "Events applied while loading aggregates",
+        &["aggregate_type"]).unwrap();
+}
+
+pub fn error(api: &str, e: &CreditError) {
+    ERRORS.with_label_values(&[api, e.name()]).inc();
+}
+
+// every metric in the Prometheus text format
+pub fn render() -> String {
+    let mut buf = vec![];
+    TextEncoder::new().encode(&prometheus::gather(), &mut buf).unwrap();
+    String::from_utf8(buf).unwrap()
+}
@@ -0,0 +1,61 @@
+use {CreditError, MyPool};
+
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    sql: &'static str
+}
+
+// Every schema change in the order it is applied. Released migrations must
+// never be edited or reordered, changes go into a new one at the end.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "event store", sql: include_str!("../migrations/001_event_store.sql") },
+    Migration { version: 2, name: "transfers", sql: include_str!("../migrations/002_transfers.sql") },
+    Migration { version: 3, name: "ledger", sql: include_str!("../migrations/003_ledger.sql") },
+    Migration { version: 4, name: "unique event versions", sql: include_str!("../migrations/004_unique_event_versions.sql") },
+    Migration { version: 5, name: "webhooks", sql: include_str!("../migrations/005_webhooks.sql") },
+    Migration { version: 6, name: "rebuild contract snapshots", sql: include_str!("../migrations/006_rebuild_contract_snapshots.sql") },
+    Migration { version: 7, name: "commit order checkpoints", sql: include_str!("../migrations/007_commit_order_checkpoints.sql") },
+];
+
+// Applies migrations not yet recorded in schema_migrations, each in its own
+// transaction along with its record. The table is locked while checking and
+// applying, so servers starting at the same time don't race each other.
+// Returns the versions that were applied.
+pub fn migrate(pool: &MyPool) -> Result<Vec<i64>, CreditError> {
+    let conn = pool.get().unwrap();
+    conn.batch_execute("
+        create table if not exists schema_migrations (
+            version bigint primary key,
+            name text not null,
+            applied_at timestamp not null default current_timestamp
+        );")?;
+
+    let mut applied = vec![];
+    for m in MIGRATIONS {
+        let trx = conn.transaction()?;
+        trx.execute("lock table schema_migrations in exclusive mode", &[])?;
+        if !trx.query("select 1 from schema_migrations where version = $1", &[&m.version])?.is_empty() {
+            continue
+        }
+
+        info!(version = m.version, name = m.name, "applying migration");
+        trx.batch_execute(m.sql)?;
+        trx.execute("insert into schema_migrations (version, name) values ($1, $2)", &[&m.version, &m.name])?;
+        trx.commit()?;
+        applied.push(m.version);
+    }
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_are_ordered_and_unique() {
+        for pair in MIGRATIONS.windows(2) {
+            assert!(pair[0].version < pair[1].version);
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use eventstore::{load, run_and_store, run_cmd, save_events_in};
+use {Aggregate, Amount, Contract, CreditCommand, CreditError, CreditType, MyPool, PersonId};
+use self::PersonEvent::*;
+
+type R = Result<Vec<PersonEvent>, CreditError>;
+
+// A customer owning one or more contracts
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub struct Person {
+    id: PersonId,
+    version: i64,
+    contracts: Vec<i64>,
+    reservation_limits: HashMap<CreditType, Amount>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum PersonEvent {
+    ContractAttached(i64),
+    ReservationLimitSet {
+        credit_type: CreditType,
+        limit: Option<Amount>
+    },
+    ReservationApproved {
+        contract: i64,
+        id: Uuid,
+        credit_type: CreditType,
+        amount: Amount
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum PersonCommand {
+    // Make a contract part of this customer
+    AttachContract(i64),
+    // Cap the total of a credit type reserved across all contracts, or lift
+    // the cap with None
+    SetReservationLimit(CreditType, Option<Amount>),
+    // Check that a reservation on one of the contracts keeps the customer
+    // within its limit for the credit type, given what is already reserved
+    // of that type across contracts
+    ApproveReservation {
+        contract: i64,
+        id: Uuid,
+        credit_type: CreditType,
+        amount: Amount,
+        reserved: Amount
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct CreditTypeBalance {
+    pub available: Amount,
+    pub reserved: Amount,
+}
+
+// What a customer has across its contracts, by credit type
+#[derive(Debug, Default, Serialize)]
+pub struct CustomerBalance {
+    pub balances: BTreeMap<CreditType, CreditTypeBalance>,
+}
+
+impl CustomerBalance {
+    pub fn of(&self, credit_type: &str) -> &CreditTypeBalance {
+        static EMPTY: CreditTypeBalance = CreditTypeBalance { available: 0, reserved: 0 };
+        self.balances.get(credit_type).unwrap_or(&EMPTY)
+    }
+}
+
+impl Person {
+    fn attach_contract(&self, contract: i64) -> R {
+        if self.contracts.contains(&contract) {
+            return Ok(vec![])
+        }
+
+        Ok(vec![ContractAttached(contract)])
+    }
+
+    fn set_reservation_limit(&self, credit_type: &str, limit: Option<Amount>) -> R {
+        match limit {
+            Some(l) if l < 0 => Err(CreditError::InvalidAmount(l)),
+            _ => Ok(vec![ReservationLimitSet { credit_type: credit_type.to_string(), limit }])
+        }
+    }
+
+    fn approve_reservation(&self, contract: i64, id: Uuid, credit_type: &str, amount: Amount, reserved: Amount) -> R {
+        if !self.contracts.contains(&contract) {
+            return Err(CreditError::ContractNotAttached)
+        }
+
+        if let Some(&limit) = self.reservation_limits.get(credit_type) {
+            if reserved+amount > limit {
+                return Err(CreditError::ReservationLimitExceeded {limit, needs: reserved+amount-limit })
+            }
+        }
+
+        Ok(vec![ReservationApproved { contract, id, credit_type: credit_type.to_string(), amount }])
+    }
+}
+
+impl Aggregate for Person {
+    type Item = PersonEvent;
+    type Cmd = PersonCommand;
+    type Error = CreditError;
+
+    const TYPE: &'static str = "person";
+
+    fn new(id: i64) -> Self {
+        Person { id, ..Default::default() }
+    }
+
+    fn version(&self) -> i64 {
+        self.version
+    }
+
+    fn id(&self) -> i64 {
+        self.id
+    }
+
+    fn handle(&self, cmd: &Self::Cmd) -> Result<Vec<Self::Item>, Self::Error> {
+        match cmd {
+            &PersonCommand::AttachContract(contract) => self.attach_contract(contract),
+            &PersonCommand::SetReservationLimit(ref credit_type, limit) => self.set_reservation_limit(credit_type, limit),
+            &PersonCommand::ApproveReservation { contract, id, ref credit_type, amount, reserved } =>
+                self.approve_reservation(contract, id, credit_type, amount, reserved)
+        }
+    }
+
+    fn apply(&mut self, evt: &Self::Item) -> () {
+        self.version += 1;
+
+        match evt {
+            &ContractAttached(contract) => self.contracts.push(contract),
+            &ReservationLimitSet { ref credit_type, limit: Some(limit) } => {
+                self.reservation_limits.insert(credit_type.clone(), limit);
+            },
+            &ReservationLimitSet { ref credit_type, limit: None } => {
+                self.reservation_limits.remove(credit_type);
+            },
+            // only recorded so reservations across contracts are serialized
+            // through the person's version
+            &ReservationApproved { .. } => {}
+        };
+    }
+}
+
+// Attaches a contract to a customer, recording ownership on both sides in
+// one transaction
+pub fn attach_contract(person: PersonId, contract: i64, pool: &MyPool) -> Result<(), CreditError> {
+    let mut p: Person = load(person, pool)?;
+    let mut c: Contract = load(contract, pool)?;
+    let (p_version, c_version) = (p.version(), c.version());
+
+    let c_evts = run_cmd(&mut c, CreditCommand::AssignOwner(person))?;
+    let p_evts = run_cmd(&mut p, PersonCommand::AttachContract(contract))?;
+
+    let conn = pool.get().unwrap();
+    let trx = conn.transaction()?;
+    if !c_evts.is_empty() {
+        save_events_in(&trx, c_version, &c, c_evts)?;
+    }
+    if !p_evts.is_empty() {
+        save_events_in(&trx, p_version, &p, p_evts)?;
+    }
+    trx.commit()?;
+    Ok(())
+}
+
+// Reserves credits on a contract. Contracts owned by a customer must also
+// have the reservation approved by the customer, which is stored in the
+// same transaction so concurrent reservations on sibling contracts conflict.
+// The customer's limit only counts what is reserved of the same credit type.
+pub fn reserve_credits(contract: i64, id: Uuid, credit_type: CreditType, amount: Amount, pool: &MyPool) -> Result<Contract, CreditError> {
+    let mut c: Contract = load(contract, pool)?;
+    let owner = match c.owner {
+        Some(owner) => owner,
+        None => {
+            run_and_store(&mut c, CreditCommand::ReserveCredits(id, credit_type, amount), pool)?;
+            return Ok(c)
+        }
+    };
+
+    let mut p: Person = load(owner, pool)?;
+    let mut reserved = c.reserved(&credit_type);
+    for &other in p.contracts.iter().filter(|&&other| other != contract) {
+        let o: Contract = load(other, pool)?;
+        reserved += o.reserved(&credit_type);
+    }
+
+    let (p_version, c_version) = (p.version(), c.version());
+    let approve = PersonCommand::ApproveReservation { contract, id, credit_type: credit_type.clone(), amount, reserved };
+    let p_evts = run_cmd(&mut p, approve)?;
+    let c_evts = run_cmd(&mut c, CreditCommand::ReserveCredits(id, credit_type, amount))?;
+
+    let conn = pool.get().unwrap();
+    let trx = conn.transaction()?;
+    save_events_in(&trx, p_version, &p, p_evts)?;
+    save_events_in(&trx, c_version, &c, c_evts)?;
+    trx.commit()?;
+    Ok(c)
+}
+
+// Sums up the balances of every contract owned by a customer, keeping each
+// credit type apart
+pub fn customer_balance(person: PersonId, pool: &MyPool) -> Result<CustomerBalance, CreditError> {
+    let p: Person = load(person, pool)?;
+    let mut balance = CustomerBalance::default();
+    for &contract in p.contracts.iter() {
+        let c: Contract = load(contract, pool)?;
+        add_contract(&mut balance, &c);
+    }
+    Ok(balance)
+}
+
+fn add_contract(balance: &mut CustomerBalance, c: &Contract) {
+    for (t, b) in c.balances.iter() {
+        balance.balances.entry(t.clone()).or_default().available += b.amount;
+    }
+    for r in c.reservations.values().chain(c.allocations.values()) {
+        balance.balances.entry(r.credit_type.clone()).or_default().reserved += r.amount;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {default_credit_type, DEFAULT_CREDIT_TYPE};
+
+    #[test]
+    fn it_attaches_contract_once() {
+        let mut p = Person::new(1);
+        run_cmd(&mut p, PersonCommand::AttachContract(5)).unwrap();
+        let evts = run_cmd(&mut p, PersonCommand::AttachContract(5)).unwrap();
+        assert!(evts.is_empty());
+        assert_eq!(p.contracts, vec![5]);
+    }
+
+    #[test]
+    fn it_approves_within_limit() {
+        let mut p = with_contract(5);
+        run_cmd(&mut p, PersonCommand::SetReservationLimit(default_credit_type(), Some(10))).unwrap();
+        let id = Uuid::new_v4();
+        run_cmd(&mut p, approve(5, id, &default_credit_type(), 4, 6)).unwrap();
+        run_cmd(&mut p, approve(5, id, &default_credit_type(), 5, 6))
+            .expect_err("should not approve beyond limit");
+    }
+
+    #[test]
+    fn it_limits_each_credit_type_apart() {
+        let mut p = with_contract(5);
+        run_cmd(&mut p, PersonCommand::SetReservationLimit("gpu".to_string(), Some(10))).unwrap();
+        run_cmd(&mut p, approve(5, Uuid::new_v4(), &default_credit_type(), 50, 0)).unwrap();
+        run_cmd(&mut p, approve(5, Uuid::new_v4(), "gpu", 11, 0))
+            .expect_err("should not approve beyond limit of the type");
+        run_cmd(&mut p, PersonCommand::SetReservationLimit("gpu".to_string(), None)).unwrap();
+        run_cmd(&mut p, approve(5, Uuid::new_v4(), "gpu", 11, 0)).unwrap();
+    }
+
+    #[test]
+    fn it_does_not_approve_unknown_contract() {
+        let mut p = with_contract(5);
+        run_cmd(&mut p, approve(6, Uuid::new_v4(), &default_credit_type(), 1, 0))
+            .expect_err("should not approve for contract of someone else");
+    }
+
+    #[test]
+    fn it_sums_balances_by_credit_type() {
+        let mut c = Contract::new(5);
+        run_cmd(&mut c, CreditCommand::AddCredits(default_credit_type(), 10)).unwrap();
+        run_cmd(&mut c, CreditCommand::AddCredits("gpu".to_string(), 3)).unwrap();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(Uuid::new_v4(), "gpu".to_string(), 2)).unwrap();
+        let mut balance = CustomerBalance::default();
+        add_contract(&mut balance, &c);
+        add_contract(&mut balance, &c);
+        assert_eq!(balance.of(DEFAULT_CREDIT_TYPE), &CreditTypeBalance { available: 20, reserved: 0 });
+        assert_eq!(balance.of("gpu"), &CreditTypeBalance { available: 2, reserved: 4 });
+    }
+
+    fn approve(contract: i64, id: Uuid, credit_type: &str, amount: Amount, reserved: Amount) -> PersonCommand {
+        PersonCommand::ApproveReservation { contract, id, credit_type: credit_type.to_string(), amount, reserved }
+    }
+
+    fn with_contract(contract: i64) -> Person {
+        let mut p = Person::new(1);
+        run_cmd(&mut p, PersonCommand::AttachContract(contract)).unwrap();
+        p
+    }
+}
@@ -0,0 +1,379 @@
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{self, Value};
+use prometheus;
+use tiny_http::{Header, Method, Request, Response, Server};
+use uuid::Uuid;
+
+use api::{credit_type, execute, ErrorKind};
+use auth::{authorize, Access, Auth, Principal};
+use eventstore::load;
+use ledger;
+use metrics;
+use person;
+use transfer::{transfer_credits, transfer_id};
+use {Amount, Contract, CreditCommand, CreditError, CreditType, MyPool, DEFAULT_CREDIT_TYPE};
+use config::Config;
+use shutdown::{Shutdown, POLL_INTERVAL};
+
+#[derive(Debug, PartialEq)]
+enum Route {
+    GetAccount(i64),
+    AddCredits(i64),
+    ReserveCredits(i64),
+    AllocateCredits(i64, String),
+    SpendReservation(i64, String),
+    CancelReservation(i64, String),
+    SetBalanceAlert(i64),
+    TransferCredits,
+    GetCustomer(i64),
+    AttachContract(i64),
+    GetTrialBalance,
+    Metrics
+}
+
+impl Route {
+    // name of the operation, used to label metrics
+    fn name(&self) -> &'static str {
+        match self {
+            Route::GetAccount(_) => "GetAccount",
+            Route::AddCredits(_) => "AddCredits",
+            Route::ReserveCredits(_) => "ReserveCredits",
+            Route::AllocateCredits(..) => "AllocateCredits",
+            Route::SpendReservation(..) => "SpendReservation",
+            Route::CancelReservation(..) => "CancelReservation",
+            Route::SetBalanceAlert(_) => "SetBalanceAlert",
+            Route::TransferCredits => "TransferCredits",
+            Route::GetCustomer(_) => "GetCustomer",
+            Route::AttachContract(_) => "AttachContract",
+            Route::GetTrialBalance => "GetTrialBalance",
+            Route::Metrics => "Metrics"
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AddCreditsBody {
+    #[serde(default)]
+    credit_type: String,
+    amount: Amount
+}
+
+#[derive(Deserialize)]
+struct ReserveCreditsBody {
+    id: String,
+    #[serde(default)]
+    credit_type: String,
+    amount: Amount
+}
+
+#[derive(Deserialize)]
+struct SetBalanceAlertBody {
+    #[serde(default)]
+    credit_type: String,
+    // null to stop alerting
+    threshold: Option<Amount>
+}
+
+#[derive(Deserialize)]
+struct TransferCreditsBody {
+    #[serde(default)]
+    id: String,
+    source: i64,
+    target: i64,
+    #[serde(default)]
+    credit_type: String,
+    amount: Amount
+}
+
+#[derive(Deserialize)]
+struct AttachContractBody {
+    account: i64
+}
+
+// What the account routes answer with, the balances GetAccountStatus gives
+// over gRPC rather than the whole contract
+#[derive(Debug, PartialEq, Serialize)]
+struct AccountStatus {
+    amount: Amount,
+    balances: Vec<AccountBalance>
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct AccountBalance {
+    credit_type: CreditType,
+    amount: Amount,
+    spent: Amount
+}
+
+fn account_status(c: &Contract) -> AccountStatus {
+    let mut balances: Vec<AccountBalance> = c.balances.iter().map(|(t, b)| AccountBalance {
+        credit_type: t.clone(),
+        amount: b.amount,
+        spent: b.spent
+    }).collect();
+    balances.sort_by(|a, b| a.credit_type.cmp(&b.credit_type));
+    AccountStatus { amount: c.balance(DEFAULT_CREDIT_TYPE).amount, balances }
+}
+
+// runs the command and answers with the account status it leaves behind
+fn status_after(account: i64, cmd: CreditCommand, pool: &MyPool) -> Result<Value, CreditError> {
+    json(&account_status(&execute(account, cmd, pool)?))
+}
+
+fn route(method: &Method, url: &str) -> Option<Route> {
+    let path = url.split('?').next().unwrap_or("");
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let id = |i: usize| segments[i].parse::<i64>().ok();
+
+    match (method, segments.as_slice()) {
+        (&Method::Get, ["accounts", _]) => id(1).map(Route::GetAccount),
+        (&Method::Post, ["accounts", _, "credits"]) => id(1).map(Route::AddCredits),
+        (&Method::Post, ["accounts", _, "reservations"]) => id(1).map(Route::ReserveCredits),
+        (&Method::Post, ["accounts", _, "reservations", r, "allocate"]) => id(1).map(|a| Route::AllocateCredits(a, r.to_string())),
+        (&Method::Post, ["accounts", _, "reservations", r, "spend"]) => id(1).map(|a| Route::SpendReservation(a, r.to_string())),
+        (&Method::Delete, ["accounts", _, "reservations", r]) => id(1).map(|a| Route::CancelReservation(a, r.to_string())),
+        (&Method::Put, ["accounts", _, "alert"]) => id(1).map(Route::SetBalanceAlert),
+        (&Method::Post, ["transfers"]) => Some(Route::TransferCredits),
+        (&Method::Get, ["customers", _]) => id(1).map(Route::GetCustomer),
+        (&Method::Post, ["customers", _, "contracts"]) => id(1).map(Route::AttachContract),
+        (&Method::Get, ["ledger", "trial-balance"]) => Some(Route::GetTrialBalance),
+        (&Method::Get, ["metrics"]) => Some(Route::Metrics),
+        _ => None
+    }
+}
+
+fn parse<T: DeserializeOwned>(body: &str) -> Result<T, CreditError> {
+    serde_json::from_str(body).map_err(|e| CreditError::InvalidRequest(e.to_string()))
+}
+
+fn reservation_id(id: &str) -> Result<Uuid, CreditError> {
+    Uuid::parse_str(id).map_err(|_| CreditError::InvalidReservationId(id.to_string()))
+}
+
+fn json<T: Serialize>(value: &T) -> Result<Value, CreditError> {
+    Ok(serde_json::to_value(value)?)
+}
+
+// what a route needs the caller to be allowed, transfers being checked once
+// their body says where the credits come from
+fn access(route: &Route) -> Option<Access> {
+    match *route {
+        Route::GetAccount(account) |
+        Route::ReserveCredits(account) |
+        Route::AllocateCredits(account, _) |
+        Route::SpendReservation(account, _) |
+        Route::CancelReservation(account, _) |
+        Route::SetBalanceAlert(account) => Some(Access::Account(account)),
+        Route::GetCustomer(customer) => Some(Access::Customer(customer)),
+        Route::AddCredits(_) | Route::AttachContract(_) | Route::GetTrialBalance => Some(Access::Admin),
+        Route::TransferCredits | Route::Metrics => None
+    }
+}
+
+fn handle(route: Route, body: &str, principal: &Principal, pool: &MyPool) -> Result<Value, CreditError> {
+    if let Some(access) = access(&route) {
+        authorize(principal, access, pool)?;
+    }
+    match route {
+        Route::GetAccount(account) => {
+            let c: Contract = load(account, pool)?;
+            json(&account_status(&c))
+        },
+        Route::AddCredits(account) => {
+            let b: AddCreditsBody = parse(body)?;
+            status_after(account, CreditCommand::AddCredits(credit_type(&b.credit_type), b.amount), pool)
+        },
+        Route::ReserveCredits(account) => {
+            let b: ReserveCreditsBody = parse(body)?;
+            let cmd = CreditCommand::ReserveCredits(reservation_id(&b.id)?, credit_type(&b.credit_type), b.amount);
+            status_after(account, cmd, pool)
+        },
+        Route::AllocateCredits(account, id) =>
+            status_after(account, CreditCommand::AllocateCredits(reservation_id(&id)?), pool),
+        Route::SpendReservation(account, id) =>
+            status_after(account, CreditCommand::SpendReservation(reservation_id(&id)?), pool),
+        Route::CancelReservation(account, id) =>
+            status_after(account, CreditCommand::CancelReservation(reservation_id(&id)?), pool),
+        Route::SetBalanceAlert(account) => {
+            let b: SetBalanceAlertBody = parse(body)?;
+            status_after(account, CreditCommand::SetBalanceAlert(credit_type(&b.credit_type), b.threshold), pool)
+        },
+        Route::TransferCredits => {
+            let b: TransferCreditsBody = parse(body)?;
+            authorize(principal, Access::Account(b.source), pool)?;
+            json(&transfer_credits(transfer_id(&b.id)?, b.source, b.target, credit_type(&b.credit_type), b.amount, pool)?)
+        },
+        Route::GetCustomer(customer) => json(&person::customer_balance(customer, pool)?),
+        Route::AttachContract(customer) => {
+            let b: AttachContractBody = parse(body)?;
+            person::attach_contract(customer, b.account, pool)?;
+            json(&person::customer_balance(customer, pool)?)
+        },
+        Route::GetTrialBalance => {
+            let tb = ledger::trial_balance(pool)?;
+            let mut v = json(&tb)?;
+            v["balanced"] = Value::Bool(tb.is_balanced());
+            Ok(v)
+        },
+        // served as text before getting here
+        Route::Metrics => unreachable!()
+    }
+}
+
+fn status_code(kind: ErrorKind) -> u16 {
+    match kind {
+        ErrorKind::InvalidArgument => 400,
+        ErrorKind::NotFound => 404,
+        ErrorKind::AlreadyExists => 409,
+        ErrorKind::FailedPrecondition => 422,
+        ErrorKind::Unauthenticated => 401,
+        ErrorKind::PermissionDenied => 403,
+        ErrorKind::ResourceExhausted => 429,
+        ErrorKind::Conflict => 409,
+        ErrorKind::Internal => 500
+    }
+}
+
+fn respond(req: Request, status: u16, body: &Value) {
+    respond_with(req, status, "application/json", body.to_string())
+}
+
+fn respond_with(req: Request, status: u16, content_type: &str, body: String) {
+    let header = Header::from_bytes(&b"Content-Type"[..], content_type.as_bytes()).unwrap();
+    let resp = Response::from_data(body).with_status_code(status).with_header(header);
+    if let Err(e) = req.respond(resp) {
+        error!(error = %e, "could not respond");
+    }
+}
+
+// Errors clients can retry later carry a Retry-After header saying when
+fn respond_error(req: Request, e: &CreditError) {
+    let header = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+    let mut resp = Response::from_data(error_body(&e.message()).to_string())
+        .with_status_code(status_code(e.kind()))
+        .with_header(header);
+    if let Some(seconds) = e.retry_after() {
+        resp.add_header(Header::from_bytes(&b"Retry-After"[..], seconds.to_string().as_bytes()).unwrap());
+    }
+    if let Err(e) = req.respond(resp) {
+        error!(error = %e, "could not respond");
+    }
+}
+
+fn authorization(req: &Request) -> Option<String> {
+    req.headers().iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .map(|h| h.value.as_str().to_string())
+}
+
+fn serve(mut req: Request, auth: &Auth, pool: &MyPool) {
+    let span = info_span!("http", method = %req.method(), url = %req.url());
+    let _enter = span.enter();
+
+    let route = match route(req.method(), req.url()) {
+        Some(route) => route,
+        None => return respond(req, 404, &error_body("no such resource"))
+    };
+    if route == Route::Metrics {
+        return respond_with(req, 200, prometheus::TEXT_FORMAT, metrics::render())
+    }
+    let _timer = metrics::REQUEST_DURATION.with_label_values(&["http", route.name()]).start_timer();
+
+    let mut body = String::new();
+    if let Err(e) = req.as_reader().read_to_string(&mut body) {
+        return respond(req, 400, &error_body(&e.to_string()))
+    }
+
+    let result = auth.authenticate(authorization(&req).as_deref())
+        .and_then(|principal| handle(route, &body, &principal, pool));
+    match result {
+        Ok(v) => respond(req, 200, &v),
+        Err(e) => {
+            debug!(error = ?e, "request failed");
+            metrics::error("http", &e);
+            respond_error(req, &e)
+        }
+    }
+}
+
+fn error_body(message: &str) -> Value {
+    let mut v = Value::Object(Default::default());
+    v["error"] = Value::String(message.to_string());
+    v
+}
+
+// Serves the same operations as the gRPC server as JSON over HTTP, on a few
+// threads of its own. They stop taking requests once shutdown is requested,
+// and the listener closes when the last of them has finished.
+pub fn start_server(pool: MyPool, config: &Config, shutdown: &Shutdown) -> Vec<JoinHandle<()>> {
+    let server = Arc::new(Server::http(config.http_address.as_str()).unwrap());
+    info!(address = %server.server_addr(), "serving http");
+    let auth = Auth::new(config);
+
+    (0..config.http_workers).map(|_| {
+        let server = server.clone();
+        let pool = pool.clone();
+        let auth = auth.clone();
+        let shutdown = shutdown.clone();
+        thread::spawn(move || {
+            while !shutdown.is_requested() {
+                match server.recv_timeout(POLL_INTERVAL) {
+                    Ok(Some(req)) => serve(req, &auth, &pool),
+                    Ok(None) => {},
+                    Err(e) => error!(error = %e, "could not receive request")
+                }
+            }
+        })
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eventstore::run_cmd;
+    use {default_credit_type, Aggregate};
+
+    #[test]
+    fn it_routes_account_operations() {
+        assert_eq!(route(&Method::Get, "/accounts/5"), Some(Route::GetAccount(5)));
+        assert_eq!(route(&Method::Post, "/accounts/5/credits"), Some(Route::AddCredits(5)));
+        assert_eq!(route(&Method::Delete, "/accounts/5/reservations/abc"), Some(Route::CancelReservation(5, "abc".to_string())));
+        assert_eq!(route(&Method::Put, "/accounts/5/alert"), Some(Route::SetBalanceAlert(5)));
+    }
+
+    #[test]
+    fn only_admins_add_credits() {
+        assert_eq!(access(&Route::AddCredits(5)), Some(Access::Admin));
+        assert_eq!(access(&Route::SpendReservation(5, "abc".to_string())), Some(Access::Account(5)));
+        assert_eq!(access(&Route::GetCustomer(3)), Some(Access::Customer(3)));
+    }
+
+    #[test]
+    fn it_does_not_route_unknown_paths() {
+        assert_eq!(route(&Method::Get, "/accounts/five"), None);
+        assert_eq!(route(&Method::Put, "/accounts/5"), None);
+        assert_eq!(route(&Method::Get, "/nothing"), None);
+    }
+
+    #[test]
+    fn it_answers_with_balances_rather_than_the_contract() {
+        let mut c = Contract::new(5);
+        run_cmd(&mut c, CreditCommand::AddCredits(default_credit_type(), 10)).unwrap();
+        run_cmd(&mut c, CreditCommand::AddCredits("gpu".to_string(), 3)).unwrap();
+        let id = Uuid::new_v4();
+        run_cmd(&mut c, CreditCommand::ReserveCredits(id, default_credit_type(), 4)).unwrap();
+        run_cmd(&mut c, CreditCommand::SpendReservation(id)).unwrap();
+
+        let expected: Value = serde_json::from_str(r#"{
+            "amount": 6,
+            "balances": [
+                {"credit_type": "default", "amount": 6, "spent": 4},
+                {"credit_type": "gpu", "amount": 3, "spent": 0}
+            ]
+        }"#).unwrap();
+        assert_eq!(json(&account_status(&c)).unwrap(), expected);
+    }
+}
@@ -1,81 +1,227 @@
-use grpcio::{Environment, ServerBuilder, RpcContext, UnarySink};
+use grpcio::{Environment, Server, ServerBuilder, RpcContext, RpcStatus, RpcStatusCode, UnarySink};
 use futures::Future;
 use std::sync::Arc;
-use std::io;
-use std::io::Read;
-use stopwatch::Stopwatch;
+use std::sync::mpsc;
+use std::net::SocketAddr;
+use std::thread;
+use std::time::Instant;
 
 use eventstore::*;
-use credits::{AccountStatusRequest, AccountStatus, AddCreditsCommand, AddCreditsResponse};
+use metrics;
+use api::{credit_type, execute, ErrorKind};
+use credits::{AccountStatusRequest, AccountStatus, AddCreditsCommand, AddCreditsResponse, TransferCreditsCommand, TransferCreditsResponse};
+use credits::{CreditBalance, ReserveCreditsCommand, ReserveCreditsResponse, AttachContractCommand, AttachContractResponse, CustomerStatusRequest, CustomerStatus, CustomerBalance};
+use credits::{TrialBalanceRequest, TrialBalance, LedgerBalance};
 use credits_grpc::{Credits, create_credits};
+use health_grpc::create_health;
+use reflection_grpc::create_server_reflection;
+use healthcheck::HealthSvc;
+use discovery::ReflectionSvc;
+use shutdown::Shutdown;
+use tls;
+use auth::{authorize, Access, Auth, Principal};
+use transfer::{transfer_credits, transfer_id};
+use person;
+use ledger;
+use uuid::Uuid;
 use MyPool;
-use {CreditCommand, CreditError};
+use config::Config;
+use {Contract, CreditCommand, CreditError, DEFAULT_CREDIT_TYPE};
 
 #[derive(Clone)]
 struct CreditsSvc {
-    pool: MyPool
+    pool: MyPool,
+    auth: Auth
+}
+
+// the bearer token sent in the call's metadata, if any
+fn authorization(ctx: &RpcContext) -> Option<String> {
+    ctx.request_headers().iter()
+        .find(|&(key, _)| key == "authorization")
+        .map(|(_, value)| String::from_utf8_lossy(value).into_owned())
 }
 
 impl CreditsSvc {
     // helper that takes care of some of the boilerplate stuff around
-    // handling grpc requests and responses
+    // handling grpc requests and responses, including finding out who the
+    // caller is
     fn doit<Treq, Tresp, F>(&mut self, ctx: RpcContext, req: Treq, sink: UnarySink<Tresp>, mut act: F) where
         Treq : ::protobuf::Message,
         Tresp : ::protobuf::Message,
-        F : FnMut(&mut Self, Treq) -> Result<Tresp, CreditError>
+        F : FnMut(&mut Self, &Principal, Treq) -> Result<Tresp, CreditError>
     {
-        let sw = Stopwatch::start_new();
-        debug!("{:?}", req);
-        let res = act(self, req);
+        let method = String::from_utf8_lossy(ctx.method()).rsplit('/').next().unwrap_or("").to_string();
+        let span = info_span!("rpc", method = %method, principal = ::tracing::field::Empty);
+        let _enter = span.enter();
+        let _timer = metrics::REQUEST_DURATION.with_label_values(&["grpc", &method]).start_timer();
+        debug!(request = ?req);
+        let res = self.auth.authenticate(authorization(&ctx).as_deref()).and_then(|p| {
+            span.record("principal", p.sub.as_str());
+            act(self, &p, req)
+        });
         match res {
             Ok(resp) => {
-                let f = sink.success(resp).map_err(|e| error!("{}", e));
-                debug!("done in {}", sw.elapsed_ms());
+                let f = sink.success(resp).map_err(|e| error!(error = %e, "could not respond"));
                 ctx.spawn(f)
             },
-            Err(e) => error!("{:?}", e)
+            Err(e) => {
+                debug!(error = ?e, "request failed");
+                metrics::error("grpc", &e);
+                let f = sink.fail(RpcStatus::new(status_code(e.kind()), Some(e.message()))).map_err(|e| error!(error = %e, "could not respond"));
+                ctx.spawn(f)
+            }
         }
     }
 }
 
+fn status_code(kind: ErrorKind) -> RpcStatusCode {
+    match kind {
+        ErrorKind::InvalidArgument => RpcStatusCode::InvalidArgument,
+        ErrorKind::NotFound => RpcStatusCode::NotFound,
+        ErrorKind::AlreadyExists => RpcStatusCode::AlreadyExists,
+        ErrorKind::FailedPrecondition => RpcStatusCode::FailedPrecondition,
+        ErrorKind::Unauthenticated => RpcStatusCode::Unauthenticated,
+        ErrorKind::PermissionDenied => RpcStatusCode::PermissionDenied,
+        ErrorKind::ResourceExhausted => RpcStatusCode::ResourceExhausted,
+        ErrorKind::Conflict => RpcStatusCode::Aborted,
+        ErrorKind::Internal => RpcStatusCode::Internal
+    }
+}
+
 impl Credits for CreditsSvc {
     fn get_account_status(&mut self, ctx: RpcContext, req: AccountStatusRequest, sink: UnarySink<AccountStatus>) {
-        self.doit(ctx, req, sink, |s, req| {
-            let agg = load(req.account, &s.pool)?;
+        self.doit(ctx, req, sink, |s, p, req| {
+            authorize(p, Access::Account(req.account), &s.pool)?;
+            let agg: Contract = load(req.account, &s.pool)?;
             let mut r = AccountStatus::new();
-            r.set_amount(agg.amount);
+            r.set_amount(agg.balance(DEFAULT_CREDIT_TYPE).amount);
+            for (t, b) in agg.balances.iter() {
+                let mut balance = CreditBalance::new();
+                balance.set_credit_type(t.clone());
+                balance.set_amount(b.amount);
+                balance.set_spent(b.spent);
+                r.mut_balances().push(balance);
+            }
             Ok(r)
         })
     }
 
     fn add_credits(&mut self, ctx: RpcContext, req: AddCreditsCommand, sink: UnarySink<AddCreditsResponse>) {
-        self.doit(ctx, req, sink, |s, req| {
-            let mut agg = load(req.account, &s.pool)?;
-            run_and_store(&mut agg, CreditCommand::AddCredits(req.amount), &s.pool)?;
+        self.doit(ctx, req, sink, |s, p, req| {
+            authorize(p, Access::Admin, &s.pool)?;
+            let t = credit_type(&req.credit_type);
+            let agg = execute(req.account, CreditCommand::AddCredits(t.clone(), req.amount), &s.pool)?;
             let mut resp = AddCreditsResponse::new();
-            resp.set_new_amount(agg.amount);
+            resp.set_new_amount(agg.balance(&t).amount);
+            Ok(resp)
+        })
+    }
+
+    fn transfer_credits(&mut self, ctx: RpcContext, req: TransferCreditsCommand, sink: UnarySink<TransferCreditsResponse>) {
+        self.doit(ctx, req, sink, |s, p, req| {
+            authorize(p, Access::Account(req.source), &s.pool)?;
+            let id = transfer_id(&req.id)?;
+            let t = transfer_credits(id, req.source, req.target, credit_type(&req.credit_type), req.amount, &s.pool)?;
+            let mut resp = TransferCreditsResponse::new();
+            resp.set_id(t.id.to_string());
+            resp.set_state(format!("{:?}", t.state));
+            Ok(resp)
+        })
+    }
+
+    fn reserve_credits(&mut self, ctx: RpcContext, req: ReserveCreditsCommand, sink: UnarySink<ReserveCreditsResponse>) {
+        self.doit(ctx, req, sink, |s, p, req| {
+            authorize(p, Access::Account(req.account), &s.pool)?;
+            let id = Uuid::parse_str(&req.id).map_err(|_| CreditError::InvalidReservationId(req.id.clone()))?;
+            let t = credit_type(&req.credit_type);
+            let agg = execute(req.account, CreditCommand::ReserveCredits(id, t.clone(), req.amount), &s.pool)?;
+            let mut resp = ReserveCreditsResponse::new();
+            resp.set_new_amount(agg.balance(&t).amount);
             Ok(resp)
         })
     }
+
+    fn attach_contract(&mut self, ctx: RpcContext, req: AttachContractCommand, sink: UnarySink<AttachContractResponse>) {
+        self.doit(ctx, req, sink, |s, p, req| {
+            authorize(p, Access::Admin, &s.pool)?;
+            person::attach_contract(req.customer, req.account, &s.pool)?;
+            Ok(AttachContractResponse::new())
+        })
+    }
+
+    fn get_customer_status(&mut self, ctx: RpcContext, req: CustomerStatusRequest, sink: UnarySink<CustomerStatus>) {
+        self.doit(ctx, req, sink, |s, p, req| {
+            authorize(p, Access::Customer(req.customer), &s.pool)?;
+            let balance = person::customer_balance(req.customer, &s.pool)?;
+            let mut r = CustomerStatus::new();
+            r.set_available(balance.of(DEFAULT_CREDIT_TYPE).available);
+            r.set_reserved(balance.of(DEFAULT_CREDIT_TYPE).reserved);
+            for (t, b) in balance.balances.iter() {
+                let mut by_type = CustomerBalance::new();
+                by_type.set_credit_type(t.clone());
+                by_type.set_available(b.available);
+                by_type.set_reserved(b.reserved);
+                r.mut_balances().push(by_type);
+            }
+            Ok(r)
+        })
+    }
+
+    fn get_trial_balance(&mut self, ctx: RpcContext, req: TrialBalanceRequest, sink: UnarySink<TrialBalance>) {
+        self.doit(ctx, req, sink, |s, p, _| {
+            authorize(p, Access::Admin, &s.pool)?;
+            let tb = ledger::trial_balance(&s.pool)?;
+            let mut r = TrialBalance::new();
+            r.set_balanced(tb.is_balanced());
+            r.set_total_debit(tb.total_debit);
+            r.set_total_credit(tb.total_credit);
+            r.set_unbalanced(tb.unbalanced);
+            for a in tb.accounts.into_iter() {
+                let mut b = LedgerBalance::new();
+                b.set_account(a.aggregate_id);
+                b.set_credit_type(a.credit_type);
+                b.set_ledger_account(a.account);
+                b.set_debit(a.debit);
+                b.set_credit(a.credit);
+                r.mut_accounts().push(b);
+            }
+            Ok(r)
+        })
+    }
 }
 
-pub fn start_server(pool: MyPool) {
-    let env = Arc::new(Environment::new(1));
+// Starts serving on the grpc threads and returns the server, to be stopped
+// with `stop`. Health checks report not serving once shutdown is requested.
+pub fn start_server(pool: MyPool, config: &Config, shutdown: &Shutdown) -> Server {
+    let addr: SocketAddr = config.grpc_address.parse().expect("grpc_address must be ip:port");
+    let env = Arc::new(Environment::new(config.grpc_threads));
+    let health = HealthSvc::new(pool.clone(), shutdown.clone());
     let implementation = CreditsSvc {
-        pool: pool
+        pool: pool,
+        auth: Auth::new(config)
     };
 
     let service = create_credits(implementation);
-    let mut server = ServerBuilder::new(env)
+    let builder = ServerBuilder::new(env)
         .register_service(service)
-        .bind("127.0.0.1", 5951)
-        .build()
-        .unwrap();
+        .register_service(create_health(health))
+        .register_service(create_server_reflection(ReflectionSvc));
+    let (builder, secure) = tls::bind(builder, &addr, config).unwrap_or_else(|e| panic!("{}", e));
+    let mut server = builder.build().unwrap();
 
-    info!("starting");
+    info!(address = %addr, tls = secure, "starting");
     server.start();
-    io::stdin().read(&mut [0]).unwrap();
-    info!("shutting down");
-    server.shutdown().wait();
-    info!("exiting");
+    server
+}
+
+// Stops accepting calls and waits for those in flight to complete until the
+// deadline. Returns whether they all did.
+pub fn stop(mut server: Server, deadline: Instant) -> bool {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = server.shutdown().wait();
+        let _ = tx.send(());
+    });
+    let timeout = deadline.saturating_duration_since(Instant::now());
+    rx.recv_timeout(timeout).is_ok()
 }
\ No newline at end of file
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use signal_hook::{self, iterator::Signals};
+
+// how often loops waiting for work check whether they should stop
+pub const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+// Set once the process has been asked to stop. Loops running in the
+// background check it between units of work, so they finish what they
+// are doing before stopping.
+#[derive(Debug, Clone, Default)]
+pub struct Shutdown(Arc<AtomicBool>);
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Shutdown::default()
+    }
+
+    pub fn request(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+// Blocks until the process gets SIGTERM or SIGINT
+pub fn wait_for_signal() {
+    let signals = Signals::new([signal_hook::SIGTERM, signal_hook::SIGINT])
+        .expect("could not register signal handlers");
+    if let Some(signal) = signals.forever().next() {
+        info!(signal, "received signal");
+    }
+}
+
+// Waits for the threads to finish until the deadline, returning whether all
+// of them did. Threads still running are left to die with the process.
+pub fn join_until(handles: Vec<JoinHandle<()>>, deadline: Instant) -> bool {
+    let mut finished = true;
+    for handle in handles {
+        while !handle.is_finished() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        if handle.is_finished() {
+            if handle.join().is_err() {
+                error!("thread panicked while stopping");
+            }
+        } else {
+            finished = false;
+        }
+    }
+    finished
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_gives_up_on_threads_past_the_deadline() {
+        let quick = thread::spawn(|| ());
+        let slow = thread::spawn(|| thread::sleep(Duration::from_secs(5)));
+        assert!(join_until(vec![quick], Instant::now() + Duration::from_secs(1)));
+        assert!(!join_until(vec![slow], Instant::now() + Duration::from_millis(50)));
+    }
+}
@@ -0,0 +1,137 @@
+use std::net::SocketAddr;
+use grpcio::ServerBuilder;
+use r2d2_postgres::TlsMode;
+#[cfg(feature = "tls")]
+use std::fs;
+#[cfg(feature = "tls")]
+use grpcio::{ServerCredentials, ServerCredentialsBuilder};
+#[cfg(feature = "tls")]
+use openssl::error::ErrorStack;
+#[cfg(feature = "tls")]
+use openssl::ssl::{SslConnectorBuilder, SslMethod};
+#[cfg(feature = "tls")]
+use openssl::x509::X509_FILETYPE_PEM;
+#[cfg(feature = "tls")]
+use postgres::tls::openssl::OpenSsl;
+
+use config::Config;
+
+#[cfg(feature = "tls")]
+fn read(path: &str) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|e| format!("could not read {}: {}", path, e))
+}
+
+// Binds the gRPC listener, over TLS if it is configured. Tells whether it is.
+#[cfg(feature = "tls")]
+pub fn bind(builder: ServerBuilder, addr: &SocketAddr, config: &Config) -> Result<(ServerBuilder, bool), String> {
+    Ok(match server_credentials(config)? {
+        Some(c) => (builder.bind_secure(addr.ip().to_string(), addr.port(), c), true),
+        None => (builder.bind(addr.ip().to_string(), addr.port()), false)
+    })
+}
+
+#[cfg(not(feature = "tls"))]
+pub fn bind(builder: ServerBuilder, addr: &SocketAddr, config: &Config) -> Result<(ServerBuilder, bool), String> {
+    let configured = config.grpc_tls_cert.is_some() || config.grpc_tls_key.is_some()
+        || config.grpc_tls_client_ca.is_some() || config.grpc_tls_require_client_cert;
+    if configured {
+        return Err(unsupported("grpc tls"))
+    }
+    Ok((builder.bind(addr.ip().to_string(), addr.port()), false))
+}
+
+// Credentials for the gRPC listener, or None to serve in plain text. Given a
+// client CA, certificates clients present are checked against it, and
+// clients without one are turned away if they are required.
+#[cfg(feature = "tls")]
+fn server_credentials(config: &Config) -> Result<Option<ServerCredentials>, String> {
+    let mut builder = match (&config.grpc_tls_cert, &config.grpc_tls_key) {
+        (&Some(ref cert), &Some(ref key)) => ServerCredentialsBuilder::new().add_cert(read(cert)?, read(key)?),
+        (&None, &None) if config.grpc_tls_client_ca.is_none() => return Ok(None),
+        _ => return Err("grpc tls needs both grpc_tls_cert and grpc_tls_key".to_string())
+    };
+    match config.grpc_tls_client_ca {
+        Some(ref ca) => builder = builder.root_cert(read(ca)?, config.grpc_tls_require_client_cert),
+        None if config.grpc_tls_require_client_cert =>
+            return Err("grpc_tls_require_client_cert needs grpc_tls_client_ca".to_string()),
+        None => {}
+    }
+    Ok(Some(builder.build()))
+}
+
+// How connections to Postgres are encrypted, verifying the server against
+// database_tls_ca and presenting a client certificate if one is given
+#[cfg(feature = "tls")]
+pub fn database_tls_mode(config: &Config) -> Result<TlsMode, String> {
+    match config.database_tls.as_str() {
+        "disable" => Ok(TlsMode::None),
+        "prefer" => Ok(TlsMode::Prefer(Box::new(negotiator(config)?))),
+        "require" => Ok(TlsMode::Require(Box::new(negotiator(config)?))),
+        other => Err(format!("database_tls must be disable, prefer or require, not {}", other))
+    }
+}
+
+#[cfg(not(feature = "tls"))]
+pub fn database_tls_mode(config: &Config) -> Result<TlsMode, String> {
+    match config.database_tls.as_str() {
+        "disable" => Ok(TlsMode::None),
+        "prefer" | "require" => Err(unsupported("database tls")),
+        other => Err(format!("database_tls must be disable, prefer or require, not {}", other))
+    }
+}
+
+#[cfg(feature = "tls")]
+fn negotiator(config: &Config) -> Result<OpenSsl, String> {
+    let client_cert = match (&config.database_tls_cert, &config.database_tls_key) {
+        (&Some(ref cert), &Some(ref key)) => Some((cert, key)),
+        (&None, &None) => None,
+        _ => return Err("database tls needs both database_tls_cert and database_tls_key".to_string())
+    };
+    let err = |e: ErrorStack| format!("could not set up database tls: {}", e);
+    let mut builder = SslConnectorBuilder::new(SslMethod::tls()).map_err(err)?;
+    if let Some(ref ca) = config.database_tls_ca {
+        builder.set_ca_file(ca).map_err(err)?;
+    }
+    if let Some((cert, key)) = client_cert {
+        builder.set_certificate_chain_file(cert).map_err(err)?;
+        builder.set_private_key_file(key, X509_FILETYPE_PEM).map_err(err)?;
+    }
+    Ok(OpenSsl::from(builder.build()))
+}
+
+// error for TLS settings in a build without the tls feature
+pub fn unsupported(what: &str) -> String {
+    format!("{} needs credits built with the tls feature", what)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "tls")]
+    fn it_needs_certificate_and_key_together() {
+        let mut config = Config::default();
+        assert!(server_credentials(&config).unwrap().is_none());
+        config.grpc_tls_cert = Some("server.pem".to_string());
+        server_credentials(&config).err().expect("key missing");
+
+        let mut config = Config::default();
+        config.database_tls = "require".to_string();
+        config.database_tls_key = Some("client.key".to_string());
+        database_tls_mode(&config).err().expect("certificate missing");
+        config.database_tls = "always".to_string();
+        database_tls_mode(&config).err().expect("unknown mode");
+    }
+
+    #[test]
+    #[cfg(not(feature = "tls"))]
+    fn it_refuses_tls_settings_without_the_tls_feature() {
+        let mut config = Config::default();
+        database_tls_mode(&config).expect("tls disabled");
+        config.database_tls = "prefer".to_string();
+        assert!(database_tls_mode(&config).is_err());
+        config.database_tls = "always".to_string();
+        assert!(database_tls_mode(&config).is_err());
+    }
+}
@@ -0,0 +1,331 @@
+use uuid::Uuid;
+use postgres::GenericConnection;
+use serde_json;
+
+use eventstore::{load, run_and_store, run_cmd, save_events_in};
+use person;
+use {Aggregate, Amount, Contract, CreditCommand, CreditError, CreditType, MyPool};
+
+// how many times a step is retried when another writer beat us to an aggregate
+const MAX_ATTEMPTS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TransferState {
+    // Transfer has been recorded but nothing has happened yet
+    Started,
+    // Credits are reserved on the source account
+    Reserved,
+    // Credits have been added to the target account and the reservation on
+    // the source account has been spent
+    Completed,
+    // Target could not be credited, reservation on the source must be cancelled
+    Compensating,
+    // Reservation on the source account has been cancelled
+    Cancelled,
+    // Source account could not reserve the credits
+    Failed
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Transfer {
+    pub id: Uuid,
+    pub source: i64,
+    pub target: i64,
+    pub amount: Amount,
+    pub credit_type: CreditType,
+    pub state: TransferState
+}
+
+impl Transfer {
+    fn is_finished(&self) -> bool {
+        match self.state {
+            TransferState::Completed | TransferState::Cancelled | TransferState::Failed => true,
+            _ => false
+        }
+    }
+}
+
+// Moves credits from one account to another. The reservation on the source
+// uses the transfer id, so every step can tell whether it already happened
+// when an interrupted transfer is picked up again.
+//
+// Callers may pick the id themselves. Asking again with the id of a transfer
+// already made carries on with that transfer rather than starting another,
+// so a request that failed part way can safely be retried.
+pub fn transfer_credits(id: Option<Uuid>, source: i64, target: i64, credit_type: CreditType, amount: Amount, pool: &MyPool) -> Result<Transfer, CreditError> {
+    if source == target {
+        return Err(CreditError::TransferToSelf)
+    }
+
+    if amount <= 0 {
+        return Err(CreditError::InvalidAmount(amount))
+    }
+
+    let t = Transfer {
+        id: id.unwrap_or_else(Uuid::new_v4),
+        source,
+        target,
+        amount,
+        credit_type,
+        state: TransferState::Started
+    };
+
+    let conn = pool.get().unwrap();
+    conn.execute(
+        "insert into transfers (id, finished, payload) values ($1, false, $2) on conflict(id) do nothing",
+        &[&t.id.to_string(), &serde_json::to_string(&t)?])?;
+    let mut existing = load_transfer(&*conn, t.id)?;
+    if (existing.source, existing.target, existing.amount, &existing.credit_type) != (source, target, amount, &t.credit_type) {
+        return Err(CreditError::InvalidRequest(format!("transfer {} was made with other details", t.id)))
+    }
+    if !existing.is_finished() {
+        run_transfer(&mut existing, pool)?;
+    }
+    Ok(existing)
+}
+
+// id a caller picked for a transfer, if any
+pub fn transfer_id(id: &str) -> Result<Option<Uuid>, CreditError> {
+    if id.is_empty() {
+        return Ok(None)
+    }
+    Uuid::parse_str(id).map(Some).map_err(|_| CreditError::InvalidRequest(format!("invalid transfer id {}", id)))
+}
+
+// Continues transfers that were interrupted before reaching a final state
+pub fn resume_transfers(pool: &MyPool) -> Result<(), CreditError> {
+    let conn = pool.get().unwrap();
+    let rows = conn.query("select payload from transfers where not finished order by ts asc", &[])?;
+    for row in rows.iter() {
+        let payload: String = row.get(0);
+        let mut t: Transfer = serde_json::from_str(&payload)?;
+        info!(transfer = %t.id, state = ?t.state, "resuming transfer");
+        if let Err(e) = run_transfer(&mut t, pool) {
+            error!(transfer = %t.id, error = ?e, "transfer failed");
+        }
+    }
+    Ok(())
+}
+
+fn run_transfer(t: &mut Transfer, pool: &MyPool) -> Result<(), CreditError> {
+    let mut failure = None;
+
+    while !t.is_finished() {
+        match t.state {
+            TransferState::Started => match retrying(|| reserve(t, pool)) {
+                Ok(()) => set_state(t, TransferState::Reserved, pool)?,
+                Err(e) => {
+                    if is_transient(&e) {
+                        return Err(e)
+                    }
+                    set_state(t, TransferState::Failed, pool)?;
+                    failure = Some(e);
+                }
+            },
+            TransferState::Reserved => match retrying(|| complete(t, pool)) {
+                Ok(()) => {},
+                Err(e) => {
+                    if is_transient(&e) {
+                        return Err(e)
+                    }
+                    set_state(t, TransferState::Compensating, pool)?;
+                    failure = Some(e);
+                }
+            },
+            TransferState::Compensating => {
+                retrying(|| cancel(t, pool))?;
+                set_state(t, TransferState::Cancelled, pool)?;
+            },
+            TransferState::Completed | TransferState::Cancelled | TransferState::Failed => {}
+        }
+    }
+
+    // another run may have completed the transfer after this one gave up
+    match failure {
+        Some(e) if t.state != TransferState::Completed => Err(e),
+        _ => Ok(())
+    }
+}
+
+fn reserve(t: &Transfer, pool: &MyPool) -> Result<(), CreditError> {
+    let source: Contract = load(t.source, pool)?;
+    if source.reservations.contains_key(&t.id) || source.spendings.contains_key(&t.id) {
+        return Ok(())
+    }
+    person::reserve_credits(t.source, t.id, t.credit_type.clone(), t.amount, pool)?;
+    Ok(())
+}
+
+// adds the credits to the target, spends the reservation on the source and
+// records the transfer as completed in one transaction, so the target can
+// neither be credited twice nor keep credits the source never gave up
+fn complete(t: &mut Transfer, pool: &MyPool) -> Result<(), CreditError> {
+    let mut source: Contract = load(t.source, pool)?;
+    if source.spendings.contains_key(&t.id) {
+        // another run of this transfer completed it first
+        return set_state(t, TransferState::Completed, pool)
+    }
+    if !source.reservations.contains_key(&t.id) {
+        // reservation expired or was cancelled before we got this far
+        return Err(CreditError::ReservationNotFound)
+    }
+
+    let mut target: Contract = load(t.target, pool)?;
+    let (source_version, target_version) = (source.version(), target.version());
+    let target_evts = run_cmd(&mut target, CreditCommand::AddCredits(t.credit_type.clone(), t.amount))?;
+    let source_evts = run_cmd(&mut source, CreditCommand::SpendReservation(t.id))?;
+
+    let conn = pool.get().unwrap();
+    let trx = conn.transaction()?;
+    save_events_in(&trx, target_version, &target, target_evts)?;
+    save_events_in(&trx, source_version, &source, source_evts)?;
+    let state = t.state;
+    t.state = TransferState::Completed;
+    if !save_transfer(&trx, t, state)? {
+        t.state = state;
+        return Err(CreditError::ConcurrencyError)
+    }
+    trx.commit()?;
+    Ok(())
+}
+
+fn cancel(t: &Transfer, pool: &MyPool) -> Result<(), CreditError> {
+    let mut source: Contract = load(t.source, pool)?;
+    if !source.reservations.contains_key(&t.id) {
+        return Ok(())
+    }
+    run_and_store(&mut source, CreditCommand::CancelReservation(t.id), pool)
+}
+
+// Moves the transfer on unless another run of it got there first, in which
+// case it carries on from wherever that run left it. A run that lost the race
+// after reserving releases the reservation if the transfer went nowhere.
+fn set_state(t: &mut Transfer, state: TransferState, pool: &MyPool) -> Result<(), CreditError> {
+    let expected = t.state;
+    t.state = state;
+    let conn = pool.get().unwrap();
+    if save_transfer(&*conn, t, expected)? {
+        return Ok(())
+    }
+
+    *t = load_transfer(&*conn, t.id)?;
+    debug!(transfer = %t.id, state = ?t.state, "transfer moved on by another run");
+    match t.state {
+        TransferState::Cancelled | TransferState::Failed => retrying(|| cancel(t, pool)),
+        _ => Ok(())
+    }
+}
+
+fn load_transfer(conn: &dyn GenericConnection, id: Uuid) -> Result<Transfer, CreditError> {
+    let rows = conn.query("select payload from transfers where id = $1", &[&id.to_string()])?;
+    let payload: String = rows.get(0).get(0);
+    Ok(serde_json::from_str(&payload)?)
+}
+
+// saves the transfer only if it is still in the expected state, telling
+// whether it was
+fn save_transfer(conn: &dyn GenericConnection, t: &Transfer, expected: TransferState) -> Result<bool, CreditError> {
+    let payload = serde_json::to_string(t)?;
+    let expected = serde_json::to_value(expected)?;
+    let updated = conn.execute(
+        "update transfers set finished = $2, payload = $3, ts = current_timestamp
+        where id = $1 and payload::json->>'state' = $4",
+        &[&t.id.to_string(), &t.is_finished(), &payload, &expected.as_str()])?;
+    Ok(updated == 1)
+}
+
+// storage and concurrency problems leave the transfer where it is so it can
+// be resumed, everything else is a business rule saying no
+fn is_transient(e: &CreditError) -> bool {
+    match e {
+        CreditError::ConcurrencyError | CreditError::StorageError(_) | CreditError::PoolError(_) | CreditError::DataError(_) => true,
+        _ => false
+    }
+}
+
+fn retrying<F>(mut step: F) -> Result<(), CreditError> where F: FnMut() -> Result<(), CreditError> {
+    let mut attempts = 1;
+    loop {
+        match step() {
+            Err(CreditError::ConcurrencyError) if attempts < MAX_ATTEMPTS => {
+                debug!(attempt = attempts, max = MAX_ATTEMPTS, "concurrency conflict, retrying");
+                attempts += 1;
+            },
+            res => return res
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use chrono::Utc;
+    use eventstore::test_pool;
+    use default_credit_type;
+
+    // a source holding 10 credits and an empty target, with ids unused so far
+    fn accounts(pool: &MyPool) -> (i64, i64) {
+        let source = Utc::now().timestamp_nanos() / 1000;
+        let mut c: Contract = load(source, pool).unwrap();
+        run_and_store(&mut c, CreditCommand::AddCredits(default_credit_type(), 10), pool).unwrap();
+        (source, source + 1)
+    }
+
+    fn started(source: i64, target: i64, pool: &MyPool) -> Transfer {
+        let t = Transfer { id: Uuid::new_v4(), source, target, amount: 4, credit_type: default_credit_type(), state: TransferState::Started };
+        let conn = pool.get().unwrap();
+        conn.execute("insert into transfers (id, payload) values ($1, $2)", &[&t.id.to_string(), &serde_json::to_string(&t).unwrap()]).unwrap();
+        t
+    }
+
+    fn assert_moved_once(t: &Transfer, pool: &MyPool) {
+        let conn = pool.get().unwrap();
+        assert_eq!(load_transfer(&*conn, t.id).unwrap().state, TransferState::Completed);
+        let source: Contract = load(t.source, pool).unwrap();
+        let target: Contract = load(t.target, pool).unwrap();
+        assert_eq!(source.balance(&t.credit_type).amount, 6);
+        assert!(source.reservations.is_empty());
+        assert_eq!(target.balance(&t.credit_type).amount, 4);
+    }
+
+    #[test]
+    #[ignore]
+    fn it_completes_once_when_a_stale_run_carries_on() {
+        let pool = test_pool();
+        let (source, target) = accounts(&pool);
+        let mut first = started(source, target, &pool);
+        let conn = pool.get().unwrap();
+        let mut from_start = load_transfer(&*conn, first.id).unwrap();
+        let mut from_reserved = load_transfer(&*conn, first.id).unwrap();
+        from_reserved.state = TransferState::Reserved;
+
+        run_transfer(&mut first, &pool).unwrap();
+        run_transfer(&mut from_reserved, &pool).unwrap();
+        run_transfer(&mut from_start, &pool).unwrap();
+
+        assert_eq!(from_reserved.state, TransferState::Completed);
+        assert_eq!(from_start.state, TransferState::Completed);
+        assert_moved_once(&first, &pool);
+    }
+
+    #[test]
+    #[ignore]
+    fn it_completes_once_when_runs_overlap() {
+        let pool = test_pool();
+        for _ in 0..20 {
+            let (source, target) = accounts(&pool);
+            let id = Uuid::new_v4();
+            let runs: Vec<_> = (0..2).map(|_| {
+                let pool = pool.clone();
+                thread::spawn(move || transfer_credits(Some(id), source, target, default_credit_type(), 4, &pool))
+            }).collect();
+            for run in runs {
+                run.join().unwrap().unwrap();
+            }
+
+            let conn = pool.get().unwrap();
+            assert_moved_once(&load_transfer(&*conn, id).unwrap(), &pool);
+        }
+    }
+}
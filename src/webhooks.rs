@@ -0,0 +1,373 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use chrono::NaiveDateTime;
+use hmac::Mac;
+#[cfg(feature = "tls")]
+use openssl::ssl::{SslConnectorBuilder, SslMethod};
+use serde_json::{self, Value};
+
+use auth::mac;
+use config::Config;
+use ledger::{events_after_checkpoint, save_checkpoint};
+use shutdown::Shutdown;
+use tls;
+use {CreditError, MyPool};
+
+// name of this projection's checkpoint in the projections table
+const PROJECTION: &str = "webhooks";
+
+// deliveries attempted per run
+const BATCH_SIZE: i64 = 10;
+
+// seconds between attempts never grow beyond this
+const MAX_BACKOFF: u64 = 3600;
+
+// header carrying `sha256=` and the hex HMAC-SHA256 of the body, keyed with
+// the webhook's secret
+pub const SIGNATURE_HEADER: &str = "X-Credits-Signature";
+
+// What is posted to a webhook for each event of its account
+#[derive(Debug, Serialize)]
+struct Notification {
+    // the same for every attempt, for endpoints to tell repeats apart
+    delivery: i64,
+    account: i64,
+    version: i64,
+    ts: NaiveDateTime,
+    event: Value
+}
+
+// A delivery that ran out of attempts
+#[derive(Debug, Serialize)]
+pub struct DeadLetter {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub url: String,
+    pub event_id: i64,
+    pub attempts: i32,
+    pub last_response: Option<i32>,
+    pub last_error: Option<String>
+}
+
+// Subscribes an endpoint to the events of an account from now on, returning
+// the webhook's id. Only events with the given names are posted, or all of
+// them if there are none, so owners may for example take just the
+// `BalanceThresholdCrossed` alerts.
+pub fn register(account: i64, url: &str, secret: &str, events: &[String], pool: &MyPool) -> Result<i64, CreditError> {
+    let e = endpoint(url).map_err(CreditError::InvalidRequest)?;
+    if e.tls && !cfg!(feature = "tls") {
+        return Err(CreditError::InvalidRequest(tls::unsupported("https")))
+    }
+    if secret.is_empty() {
+        return Err(CreditError::InvalidRequest("webhooks need a secret to sign deliveries with".to_string()))
+    }
+    let events = if events.is_empty() { None } else { Some(events.to_vec()) };
+    let conn = pool.get()?;
+    let rows = conn.query(
+        "insert into webhooks (aggregate_id, url, secret, events) values ($1, $2, $3, $4) returning id",
+        &[&account, &url, &secret, &events])?;
+    Ok(rows.get(0).get(0))
+}
+
+// Removes a webhook along with its deliveries
+pub fn unregister(id: i64, pool: &MyPool) -> Result<(), CreditError> {
+    let conn = pool.get()?;
+    if conn.execute("delete from webhooks where id = $1", &[&id])? == 0 {
+        return Err(CreditError::InvalidRequest(format!("no webhook {}", id)))
+    }
+    Ok(())
+}
+
+pub fn dead_letters(pool: &MyPool) -> Result<Vec<DeadLetter>, CreditError> {
+    let conn = pool.get()?;
+    let rows = conn.query(
+        "select d.id, d.webhook_id, w.url, d.event_id, d.attempts, d.last_response, d.last_error
+        from webhook_deliveries d join webhooks w on w.id = d.webhook_id
+        where d.status = 'dead' order by d.id", &[])?;
+    Ok(rows.iter().map(|row| DeadLetter {
+        id: row.get(0),
+        webhook_id: row.get(1),
+        url: row.get(2),
+        event_id: row.get(3),
+        attempts: row.get(4),
+        last_response: row.get(5),
+        last_error: row.get(6)
+    }).collect())
+}
+
+// Gives a dead delivery a fresh round of attempts
+pub fn redeliver(id: i64, pool: &MyPool) -> Result<(), CreditError> {
+    let conn = pool.get()?;
+    let updated = conn.execute(
+        "update webhook_deliveries set status = 'pending', attempts = 0, next_attempt_at = current_timestamp
+        where id = $1 and status = 'dead'", &[&id])?;
+    if updated == 0 {
+        return Err(CreditError::InvalidRequest(format!("no dead delivery {}", id)))
+    }
+    Ok(())
+}
+
+// Queues a delivery to every webhook of its account for each event committed
+// since the last run. Returns how many were queued.
+pub fn enqueue(pool: &MyPool) -> Result<u64, CreditError> {
+    let conn = pool.get()?;
+    let trx = conn.transaction()?;
+
+    let (_, events) = events_after_checkpoint(&trx, PROJECTION)?;
+    let last = match events.last() {
+        Some(e) => e.checkpoint(),
+        None => return Ok(0)
+    };
+
+    let insert = trx.prepare(
+        "insert into webhook_deliveries (webhook_id, event_id)
+        select id, $2 from webhooks where aggregate_id = $1 and (events is null or $3 = any(events))
+        on conflict do nothing")?;
+    let mut queued = 0;
+    for e in events.iter() {
+        queued += insert.execute(&[&e.aggregate_id, &e.id, &event_name(&e.payload)?])?;
+    }
+    save_checkpoint(&trx, PROJECTION, last)?;
+    trx.commit()?;
+
+    if queued > 0 {
+        debug!(deliveries = queued, "queued webhook deliveries");
+    }
+    Ok(queued)
+}
+
+// name of the event variant a payload holds
+fn event_name(payload: &str) -> Result<String, CreditError> {
+    match serde_json::from_str(payload)? {
+        Value::Object(evt) => Ok(evt.keys().next().cloned().unwrap_or_default()),
+        Value::String(name) => Ok(name),
+        _ => Ok(String::new())
+    }
+}
+
+// Posts the deliveries that are due. Failed ones are retried with exponential
+// backoff, and dead-lettered once they run out of attempts. Deliveries are
+// claimed with a lease before being posted so servers share the work, and are
+// posted again once it runs out if the server stops before recording how it
+// went. No transaction is held open while posting. Returns how many were
+// delivered.
+pub fn deliver(pool: &MyPool, config: &Config, shutdown: &Shutdown) -> Result<usize, CreditError> {
+    let conn = pool.get()?;
+
+    // long enough to post the whole batch one after the other
+    let lease = config.webhook_timeout * (BATCH_SIZE as u64 + 1);
+    let rows = conn.query(
+        "with claimed as (
+            update webhook_deliveries set next_attempt_at = current_timestamp + $2 * interval '1 second'
+            where id in (
+                select id from webhook_deliveries
+                where status = 'pending' and next_attempt_at <= current_timestamp
+                order by next_attempt_at, id
+                limit $1
+                for update skip locked)
+            returning id, attempts, webhook_id, event_id)
+        select c.id, c.attempts, w.url, w.secret, e.aggregate_id, e.version, e.ts, e.payload
+        from claimed c
+        join webhooks w on w.id = c.webhook_id
+        join events e on e.id = c.event_id
+        order by c.id",
+        &[&BATCH_SIZE, &(lease as f64)])?;
+
+    let timeout = Duration::from_secs(config.webhook_timeout);
+    let mut delivered = 0;
+    for row in rows.iter() {
+        let id: i64 = row.get(0);
+        let previous: i32 = row.get(1);
+        if shutdown.is_requested() {
+            // hand it back rather than leave it until the lease runs out
+            conn.execute(
+                "update webhook_deliveries set next_attempt_at = current_timestamp where id = $1 and attempts = $2",
+                &[&id, &previous])?;
+            continue
+        }
+        let attempts = previous + 1;
+        let url: String = row.get(2);
+        let secret: String = row.get(3);
+        let payload: String = row.get(7);
+        let body = serde_json::to_string(&Notification {
+            delivery: id,
+            account: row.get(4),
+            version: row.get(5),
+            ts: row.get(6),
+            event: serde_json::from_str(&payload)?
+        })?;
+
+        let (response, error) = match post(&url, &body, &sign(&secret, &body), timeout) {
+            Ok(status) if status / 100 == 2 => {
+                conn.execute(
+                    "update webhook_deliveries set status = 'delivered', attempts = $2, last_response = $3,
+                    last_error = null, delivered_at = current_timestamp where id = $1 and attempts = $4",
+                    &[&id, &attempts, &(status as i32), &previous])?;
+                delivered += 1;
+                continue
+            },
+            Ok(status) => (Some(status as i32), format!("responded {}", status)),
+            Err(e) => (None, e)
+        };
+        if attempts >= config.webhook_max_attempts {
+            warn!(delivery = id, url = %url, attempts, error = %error, "dead-lettering webhook delivery");
+            conn.execute(
+                "update webhook_deliveries set status = 'dead', attempts = $2, last_response = $3, last_error = $4
+                where id = $1 and attempts = $5",
+                &[&id, &attempts, &response, &error, &previous])?;
+        } else {
+            let wait = backoff(config.webhook_backoff, attempts);
+            debug!(delivery = id, url = %url, attempts, error = %error, retry_in = wait, "webhook delivery failed");
+            conn.execute(
+                "update webhook_deliveries set attempts = $2, last_response = $3, last_error = $4,
+                next_attempt_at = current_timestamp + $5 * interval '1 second' where id = $1 and attempts = $6",
+                &[&id, &attempts, &response, &error, &(wait as f64), &previous])?;
+        }
+    }
+
+    if delivered > 0 {
+        debug!(deliveries = delivered, "delivered to webhooks");
+    }
+    Ok(delivered)
+}
+
+// seconds to wait after the given number of failed attempts
+fn backoff(base: u64, attempts: i32) -> u64 {
+    let doublings = (attempts.max(1) - 1).min(32) as u32;
+    base.saturating_mul(1 << doublings).min(MAX_BACKOFF)
+}
+
+fn sign(secret: &str, body: &str) -> String {
+    let code = mac(secret.as_bytes(), body).result().code();
+    let hex: String = code.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+#[derive(Debug, PartialEq)]
+struct Endpoint {
+    tls: bool,
+    host: String,
+    port: u16,
+    path: String
+}
+
+fn endpoint(url: &str) -> Result<Endpoint, String> {
+    let (tls, rest) = match (url.strip_prefix("https://"), url.strip_prefix("http://")) {
+        (Some(rest), _) => (true, rest),
+        (None, Some(rest)) => (false, rest),
+        (None, None) => return Err(format!("{} is not an http or https url", url))
+    };
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/")
+    };
+    let (host, port) = match authority.rfind(':') {
+        Some(i) => (&authority[..i], authority[i + 1..].parse().map_err(|_| format!("bad port in {}", url))?),
+        None => (authority, if tls { 443 } else { 80 })
+    };
+    if host.is_empty() {
+        return Err(format!("no host in {}", url))
+    }
+    Ok(Endpoint { tls, host: host.to_string(), port, path: path.to_string() })
+}
+
+// Posts a signed JSON body, returning the status the endpoint responded with
+fn post(url: &str, body: &str, signature: &str, timeout: Duration) -> Result<u16, String> {
+    let e = endpoint(url)?;
+    let addr = (e.host.as_str(), e.port).to_socket_addrs().map_err(|err| err.to_string())?
+        .next()
+        .ok_or_else(|| format!("{} has no address", e.host))?;
+    let stream = TcpStream::connect_timeout(&addr, timeout).map_err(|err| err.to_string())?;
+    stream.set_read_timeout(Some(timeout)).map_err(|err| err.to_string())?;
+    stream.set_write_timeout(Some(timeout)).map_err(|err| err.to_string())?;
+
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}: {}\r\nConnection: close\r\n\r\n{}",
+        e.path, e.host, e.port, body.len(), SIGNATURE_HEADER, signature, body);
+    if e.tls {
+        exchange_tls(&e.host, stream, &request)
+    } else {
+        exchange(stream, &request)
+    }
+}
+
+#[cfg(feature = "tls")]
+fn exchange_tls(host: &str, stream: TcpStream, request: &str) -> Result<u16, String> {
+    let connector = SslConnectorBuilder::new(SslMethod::tls()).map_err(|err| err.to_string())?.build();
+    exchange(connector.connect(host, stream).map_err(|err| err.to_string())?, request)
+}
+
+#[cfg(not(feature = "tls"))]
+fn exchange_tls(_host: &str, _stream: TcpStream, _request: &str) -> Result<u16, String> {
+    Err(tls::unsupported("https"))
+}
+
+fn exchange<S: Read + Write>(mut stream: S, request: &str) -> Result<u16, String> {
+    stream.write_all(request.as_bytes()).and_then(|_| stream.flush()).map_err(|e| e.to_string())?;
+    let mut status = String::new();
+    BufReader::new(stream).read_line(&mut status).map_err(|e| e.to_string())?;
+    status.split_whitespace().nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| format!("bad response {:?}", status.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::{self, JoinHandle};
+    use tiny_http::{Response, Server};
+
+    // answers one request with the given status, handing back its signature and body
+    fn stand_in(status: u16) -> (String, JoinHandle<(Option<String>, String)>) {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hooks/credits", server.server_addr());
+        let handle = thread::spawn(move || {
+            let mut req = server.recv().unwrap();
+            let signature = req.headers().iter()
+                .find(|h| h.field.equiv(SIGNATURE_HEADER))
+                .map(|h| h.value.as_str().to_string());
+            let mut body = String::new();
+            req.as_reader().read_to_string(&mut body).unwrap();
+            req.respond(Response::empty(status)).unwrap();
+            (signature, body)
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn it_posts_signed_bodies() {
+        let (url, stand_in) = stand_in(204);
+        let body = r#"{"delivery":1}"#;
+        assert_eq!(post(&url, body, &sign("secret", body), Duration::from_secs(5)), Ok(204));
+        assert_eq!(stand_in.join().unwrap(), (Some(sign("secret", body)), body.to_string()));
+
+        assert_eq!(sign("key", "The quick brown fox jumps over the lazy dog"),
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8");
+    }
+
+    #[test]
+    fn it_reports_failing_endpoints() {
+        let (url, stand_in) = stand_in(503);
+        assert_eq!(post(&url, "{}", "sha256=", Duration::from_secs(5)), Ok(503));
+        stand_in.join().unwrap();
+
+        post("ftp://127.0.0.1/", "{}", "sha256=", Duration::from_secs(5)).expect_err("should only post over http");
+        assert_eq!(endpoint("https://example.com").unwrap(),
+            Endpoint { tls: true, host: "example.com".to_string(), port: 443, path: "/".to_string() });
+    }
+
+    #[test]
+    fn it_names_events() {
+        assert_eq!(event_name(r#"{"BalanceThresholdCrossed":{"credit_type":"default","threshold":5,"available":4}}"#).unwrap(),
+            "BalanceThresholdCrossed");
+        assert_eq!(event_name(r#""AccountFrozen""#).unwrap(), "AccountFrozen");
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_a_limit() {
+        assert_eq!(backoff(10, 1), 10);
+        assert_eq!(backoff(10, 3), 40);
+        assert_eq!(backoff(10, 30), MAX_BACKOFF);
+    }
+}